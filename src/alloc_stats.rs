@@ -0,0 +1,178 @@
+//! Allocation counting, with the `alloc-stats` feature. That installs
+//! `Counting` as the global allocator, which keeps a tally for each thread
+//! so that solves running side by side don't count each other's allocations.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::fmt;
+
+/// whether this build counts allocations
+pub const ENABLED: bool = cfg!(feature = "alloc-stats");
+
+/// What a piece of code allocated
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    /// calls to allocate, including growing or shrinking an allocation
+    pub allocations: u64,
+    /// total bytes asked for
+    pub bytes: u64,
+    /// most bytes in use at once, over what was in use when it started
+    pub peak: u64,
+}
+
+impl fmt::Display for AllocStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} allocations, {} bytes, peak {} bytes",
+            self.allocations, self.bytes, self.peak
+        )
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Tally {
+    allocations: u64,
+    bytes: u64,
+    /// can go below zero when freeing what was allocated before measuring
+    live: i64,
+    peak: i64,
+}
+
+impl Tally {
+    const ZERO: Tally = Tally {
+        allocations: 0,
+        bytes: 0,
+        live: 0,
+        peak: 0,
+    };
+}
+
+thread_local! {
+    // const, so that using it never allocates
+    static TALLY: Cell<Tally> = const { Cell::new(Tally::ZERO) };
+}
+
+fn record(allocated: usize, freed: usize) {
+    // the thread local is gone while a thread is shutting down
+    let _ = TALLY.try_with(|cell| {
+        let mut tally = cell.get();
+        if allocated > 0 {
+            tally.allocations += 1;
+            tally.bytes += allocated as u64;
+        }
+        tally.live += allocated as i64 - freed as i64;
+        tally.peak = tally.peak.max(tally.live);
+        cell.set(tally);
+    });
+}
+
+/// The system allocator, counting as it goes
+pub struct Counting;
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record(0, layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            record(new_size, layout.size());
+        }
+        new_ptr
+    }
+}
+
+#[cfg(feature = "alloc-stats")]
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// Run `f`, counting what it allocates on this thread, or None if this
+/// build doesn't count allocations. Measurements can be nested.
+/// ```
+/// use aoc2021::alloc_stats::{self, ENABLED};
+///
+/// let (v, stats) = alloc_stats::measure(|| vec![0u8; 1000]);
+/// assert_eq!(1000, v.len());
+/// if ENABLED {
+///     assert!(stats.unwrap().bytes >= 1000);
+/// } else {
+///     assert_eq!(None, stats);
+/// }
+/// ```
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<AllocStats>) {
+    if !ENABLED {
+        return (f(), None);
+    }
+    let outer = TALLY.with(|cell| cell.replace(Tally::ZERO));
+    let result = f();
+    let inner = TALLY.with(|cell| cell.get());
+    TALLY.with(|cell| {
+        cell.set(Tally {
+            allocations: outer.allocations + inner.allocations,
+            bytes: outer.bytes + inner.bytes,
+            live: outer.live + inner.live,
+            peak: outer.peak.max(outer.live + inner.peak),
+        })
+    });
+    let stats = AllocStats {
+        allocations: inner.allocations,
+        bytes: inner.bytes,
+        peak: inner.peak.max(0) as u64,
+    };
+    (result, Some(stats))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_measure() {
+        let ((_, inner), stats) = measure(|| {
+            let (kept, inner) = measure(|| vec![1u64; 100]);
+            let mut grown: Vec<u8> = Vec::with_capacity(10);
+            grown.extend([0; 100]);
+            (kept, inner)
+        });
+        if !ENABLED {
+            assert_eq!(None, stats);
+            return;
+        }
+
+        let inner = inner.unwrap();
+        assert_eq!((1, 800, 800), (inner.allocations, inner.bytes, inner.peak));
+        let stats = stats.unwrap();
+        // the vec of 100 u64s, then one of 10 bytes grown to at least 100
+        assert!(stats.allocations >= 3, "{}", stats);
+        assert!(stats.bytes >= 800 + 10 + 100, "{}", stats);
+        assert!(stats.peak >= 800 + 100, "{}", stats);
+
+        // nothing allocated, nothing counted
+        assert_eq!(Some(AllocStats::default()), measure(|| 1 + 1).1);
+
+        // freeing isn't allocating
+        let v = vec![0u8; 64];
+        let (_, stats) = measure(|| drop(v));
+        assert_eq!(Some(AllocStats::default()), stats);
+    }
+}
@@ -0,0 +1,268 @@
+use crate::error::{Error, Result};
+use crate::solution::Part;
+use crate::util::DataDir;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+
+/// name of the answers file within the data directory
+pub const ANSWERS_FILE: &str = "answers.txt";
+
+/// How an answer compares with the recorded ones
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    /// recorded as a wrong answer, e.g. one that was rejected when submitted
+    KnownWrong,
+    Mismatch {
+        expected: String,
+    },
+    /// nothing recorded for this input
+    Unknown,
+}
+
+impl Verdict {
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Verdict::KnownWrong | Verdict::Mismatch { .. })
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::KnownWrong => write!(f, "known wrong"),
+            Verdict::Mismatch { expected } => write!(f, "wrong, expected {}", expected),
+            Verdict::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+#[derive(Default, Debug)]
+struct Expected {
+    correct: Option<String>,
+    wrong: Vec<String>,
+}
+
+type Key = (u8, u8, String);
+
+/// The checked-in answers, one per line as `day part input answer`:
+///
+/// ```text
+/// # comments and blank lines are ignored
+/// 12 1 day12.txt 5756
+/// 13 1 day13.txt !95
+/// ```
+///
+/// An answer starting with `!` records a known wrong answer.
+#[derive(Default, Debug)]
+pub struct Answers {
+    entries: HashMap<Key, Expected>,
+}
+
+impl Answers {
+    /// ```
+    /// use aoc2021::answers::{Answers, Verdict};
+    /// use aoc2021::solution::Part;
+    ///
+    /// let answers = Answers::parse("1 2 day1_test.txt 5\n1 2 day1.txt !4").unwrap();
+    /// assert_eq!(Verdict::Correct, answers.check(1, Part::Two, "day1_test.txt", "5"));
+    /// assert_eq!(Verdict::KnownWrong, answers.check(1, Part::Two, "day1.txt", "4"));
+    /// assert_eq!(Verdict::Unknown, answers.check(1, Part::One, "day1.txt", "4"));
+    /// ```
+    pub fn parse(raw: &str) -> Result<Self> {
+        let mut answers = Answers::default();
+
+        for (i, line) in raw.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() != 4 {
+                return Err(Error::parse(i + 1, 0, "expected: day part input answer"));
+            }
+            let day = fields[0]
+                .parse::<u8>()
+                .map_err(|_| Error::parse(i + 1, 1, format!("not a day: {}", fields[0])))?;
+            let part = match fields[1] {
+                "1" => 1,
+                "2" => 2,
+                other => return Err(Error::parse(i + 1, 0, format!("not a part: {}", other))),
+            };
+
+            let expected = answers
+                .entries
+                .entry((day, part, fields[2].to_string()))
+                .or_default();
+            if let Some(wrong) = fields[3].strip_prefix('!') {
+                expected.wrong.push(wrong.to_string());
+            } else if expected.correct.is_some() {
+                return Err(Error::parse(
+                    i + 1,
+                    0,
+                    format!("second answer for day {} part {} {}", day, part, fields[2]),
+                ));
+            } else {
+                expected.correct = Some(fields[3].to_string());
+            }
+        }
+        Ok(answers)
+    }
+
+    /// Load the answers file from the data directory, if there is one
+    pub fn load(data: &DataDir) -> Result<Self> {
+        let path = data.root().join(ANSWERS_FILE);
+        match fs::read_to_string(&path) {
+            Ok(raw) => Answers::parse(raw.as_str()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Answers::default()),
+            Err(e) => Err(Error::io(path.display().to_string(), e)),
+        }
+    }
+
+    fn get(&self, day: u8, part: Part, input: &str) -> Option<&Expected> {
+        self.entries.get(&(day, part.number(), input.to_string()))
+    }
+
+    pub fn expected(&self, day: u8, part: Part, input: &str) -> Option<&str> {
+        self.get(day, part, input)
+            .and_then(|e| e.correct.as_deref())
+    }
+
+    pub fn check(&self, day: u8, part: Part, input: &str, answer: &str) -> Verdict {
+        match self.get(day, part, input) {
+            None => Verdict::Unknown,
+            Some(e) if e.wrong.iter().any(|w| w == answer) => Verdict::KnownWrong,
+            Some(Expected {
+                correct: Some(correct),
+                ..
+            }) if correct != answer => Verdict::Mismatch {
+                expected: correct.clone(),
+            },
+            Some(Expected {
+                correct: Some(_), ..
+            }) => Verdict::Correct,
+            Some(_) => Verdict::Unknown,
+        }
+    }
+
+    /// every (day, part, input) that has a recorded answer
+    pub fn keys(&self) -> impl Iterator<Item = (u8, Part, &str)> {
+        self.entries.keys().map(|(day, part, input)| {
+            let part = if *part == 1 { Part::One } else { Part::Two };
+            (*day, part, input.as_str())
+        })
+    }
+}
+
+/// For the day tests: panic unless the answer matches the answers file
+#[cfg(test)]
+pub fn verify(day: u8, part: Part, input: &str, answer: impl fmt::Display) {
+    let answers = Answers::load(&DataDir::locate()).expect("answers file");
+    let answer = answer.to_string();
+    match answers.check(day, part, input, answer.as_str()) {
+        Verdict::Unknown => println!(
+            "day {} part {} ({}): {} (not in {})",
+            day,
+            part.number(),
+            input,
+            answer,
+            ANSWERS_FILE
+        ),
+        verdict if verdict.is_failure() => panic!(
+            "day {} part {} ({}): {} is {}",
+            day,
+            part.number(),
+            input,
+            answer,
+            verdict
+        ),
+        _ => (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let answers = Answers::parse(
+            "# comment\n\n12 1 day12.txt 5756\n13 1 day13.txt !95\n13 1 day13.txt !96\n",
+        )
+        .unwrap();
+
+        assert_eq!(Some("5756"), answers.expected(12, Part::One, "day12.txt"));
+        assert_eq!(None, answers.expected(13, Part::One, "day13.txt"));
+
+        assert_eq!(
+            Verdict::Correct,
+            answers.check(12, Part::One, "day12.txt", "5756")
+        );
+        assert_eq!(
+            Verdict::Mismatch {
+                expected: "5756".to_string()
+            },
+            answers.check(12, Part::One, "day12.txt", "5757")
+        );
+        assert_eq!(
+            Verdict::KnownWrong,
+            answers.check(13, Part::One, "day13.txt", "96")
+        );
+        assert_eq!(
+            Verdict::Unknown,
+            answers.check(13, Part::One, "day13.txt", "610")
+        );
+        assert_eq!(
+            Verdict::Unknown,
+            answers.check(12, Part::Two, "day12.txt", "5756")
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_matches!(
+            Answers::parse("1 1 day1.txt"),
+            Err(Error::Parse { line: 1, .. })
+        );
+        assert_matches!(
+            Answers::parse("\nx 1 day1.txt 5"),
+            Err(Error::Parse { line: 2, .. })
+        );
+        assert_matches!(
+            Answers::parse("1 3 day1.txt 5"),
+            Err(Error::Parse { line: 1, .. })
+        );
+        assert_matches!(
+            Answers::parse("1 1 day1.txt 5\n1 1 day1.txt 6"),
+            Err(Error::Parse { line: 2, .. })
+        );
+        assert!(Answers::load(&DataDir::new("/not/exist")).is_ok());
+    }
+
+    #[test]
+    fn test_recorded_answers() {
+        // every recorded answer still comes out the same
+        let data = DataDir::locate();
+        let answers = Answers::load(&data).unwrap();
+
+        for (day, part, input) in answers.keys() {
+            let solution = crate::day(day).expect("answers only for implemented days");
+            let raw = fs::read_to_string(data.root().join(input)).unwrap();
+            let answer = solution.run(raw.as_str(), part).unwrap();
+
+            let verdict = answers.check(day, part, input, answer.as_str());
+            assert!(
+                !verdict.is_failure(),
+                "day {} part {} ({}): {} is {}",
+                day,
+                part.number(),
+                input,
+                answer,
+                verdict
+            );
+        }
+    }
+}
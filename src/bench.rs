@@ -0,0 +1,487 @@
+use crate::alloc_stats::{self, AllocStats};
+use crate::error::{Error, Result};
+use crate::generate;
+use crate::solution::{Day, Part};
+use crate::util::{DataDir, Kind};
+use std::fmt;
+use std::io;
+use std::io::prelude::*;
+use std::time::{Duration, Instant};
+
+pub const USAGE: &str =
+    "usage: bench [--runs N] [--tsv] [--example] [--generate [--seed N] [--size N]] [--data DIR] [DAY...]";
+
+/// What is being timed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stage {
+    Parse,
+    Solve(Part),
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stage::Parse => write!(f, "parse"),
+            Stage::Solve(part) => write!(f, "part{}", part.number()),
+        }
+    }
+}
+
+/// Summary of the samples taken for one stage
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    /// ```
+    /// use aoc2021::bench::Stats;
+    /// use std::time::Duration;
+    ///
+    /// let samples = [3, 1, 2, 10].map(Duration::from_millis);
+    /// let stats = Stats::from_samples(&samples).unwrap();
+    /// assert_eq!(Duration::from_millis(1), stats.min);
+    /// assert_eq!(Duration::from_millis(2), stats.median);
+    /// assert_eq!(Duration::from_millis(10), stats.max);
+    /// ```
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        Some(Stats {
+            runs: sorted.len(),
+            min: *sorted.first()?,
+            // the lower of the two middle values for an even count
+            median: sorted[(sorted.len() - 1) / 2],
+            max: *sorted.last()?,
+        })
+    }
+}
+
+/// The timings of one stage of one day
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Timing {
+    pub day: u8,
+    pub stage: Stage,
+    pub stats: Stats,
+    /// what one run allocated, when counting allocations
+    pub allocs: Option<AllocStats>,
+}
+
+fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+/// Run a day `runs` times, timing the parse and each part separately.
+/// Each part gets a freshly parsed input, which is not part of its time.
+/// Allocations are counted on the first run, as every run makes the same ones.
+pub fn bench_day(day: &dyn Day, raw: &str, runs: usize) -> Result<Vec<Timing>> {
+    let stages = [
+        Stage::Parse,
+        Stage::Solve(Part::One),
+        Stage::Solve(Part::Two),
+    ];
+    let mut samples = vec![Vec::with_capacity(runs); stages.len()];
+    let mut allocs = vec![None; stages.len()];
+
+    for _ in 0..runs {
+        let ((parsed, elapsed), counted) = alloc_stats::measure(|| time(|| day.parse(raw)));
+        parsed?;
+        samples[0].push(elapsed);
+        allocs[0] = allocs[0].or(counted);
+
+        for (i, stage) in stages.iter().enumerate().skip(1) {
+            if let Stage::Solve(part) = stage {
+                let input = day.parse(raw)?;
+                let ((answer, elapsed), counted) =
+                    alloc_stats::measure(|| time(|| day.solve(input, *part)));
+                answer?;
+                samples[i].push(elapsed);
+                allocs[i] = allocs[i].or(counted);
+            }
+        }
+    }
+
+    Ok(stages
+        .iter()
+        .zip(samples.iter().zip(allocs))
+        .filter_map(|(stage, (samples, allocs))| {
+            Stats::from_samples(samples).map(|stats| Timing {
+                day: day.number(),
+                stage: *stage,
+                stats,
+                allocs,
+            })
+        })
+        .collect())
+}
+
+fn counts_allocs(timings: &[Timing]) -> bool {
+    timings.iter().any(|t| t.allocs.is_some())
+}
+
+/// A human readable table, in microseconds, with the allocations when they
+/// were counted
+pub fn write_table(timings: &[Timing], out: &mut impl Write) -> io::Result<()> {
+    write!(
+        out,
+        "{:>3}  {:<6} {:>5} {:>12} {:>12} {:>12}",
+        "day", "stage", "runs", "min µs", "median µs", "max µs"
+    )?;
+    if counts_allocs(timings) {
+        write!(
+            out,
+            " {:>10} {:>12} {:>12}",
+            "allocs", "bytes", "peak bytes"
+        )?;
+    }
+    writeln!(out)?;
+    for t in timings {
+        write!(
+            out,
+            "{:>3}  {:<6} {:>5} {:>12.1} {:>12.1} {:>12.1}",
+            t.day,
+            t.stage.to_string(),
+            t.stats.runs,
+            micros(t.stats.min),
+            micros(t.stats.median),
+            micros(t.stats.max)
+        )?;
+        if let Some(a) = t.allocs {
+            write!(out, " {:>10} {:>12} {:>12}", a.allocations, a.bytes, a.peak)?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+fn micros(d: Duration) -> f64 {
+    d.as_secs_f64() * 1e6
+}
+
+/// Tab separated, with a header and whole nanoseconds, for comparing runs
+pub fn write_tsv(timings: &[Timing], out: &mut impl Write) -> io::Result<()> {
+    write!(out, "day\tstage\truns\tmin_ns\tmedian_ns\tmax_ns")?;
+    if counts_allocs(timings) {
+        write!(out, "\tallocs\tbytes\tpeak_bytes")?;
+    }
+    writeln!(out)?;
+    for t in timings {
+        write!(
+            out,
+            "{}\t{}\t{}\t{}\t{}\t{}",
+            t.day,
+            t.stage,
+            t.stats.runs,
+            t.stats.min.as_nanos(),
+            t.stats.median.as_nanos(),
+            t.stats.max.as_nanos()
+        )?;
+        if let Some(a) = t.allocs {
+            write!(out, "\t{}\t{}\t{}", a.allocations, a.bytes, a.peak)?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Options {
+    /// every implemented day when empty
+    pub days: Vec<u8>,
+    pub runs: usize,
+    pub tsv: bool,
+    /// use the day's first example instead of the puzzle input
+    pub example: bool,
+    /// use a generated input instead of reading one
+    pub generate: bool,
+    pub seed: u64,
+    /// how big a generated input is, each day's own default when None
+    pub size: Option<usize>,
+    pub data: DataDir,
+}
+
+/// Parse the command line arguments (without the program name)
+pub fn parse_args(args: impl Iterator<Item = String>) -> std::result::Result<Options, String> {
+    let mut days = Vec::new();
+    let mut runs = 10;
+    let mut tsv = false;
+    let mut example = false;
+    let mut generate = false;
+    let mut seed = 0;
+    let mut size = None;
+    let mut data = None;
+
+    let mut args = args;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-n" | "--runs" => {
+                let value = args.next().ok_or("--runs needs a value")?;
+                runs = match value.parse::<usize>() {
+                    Ok(n) if n > 0 => n,
+                    _ => return Err(format!("not a number of runs: {}", value)),
+                };
+            }
+            "--tsv" => tsv = true,
+            "--example" => example = true,
+            "--generate" => generate = true,
+            "--seed" => {
+                let value = args.next().ok_or("--seed needs a value")?;
+                seed = value
+                    .parse::<u64>()
+                    .map_err(|_| format!("not a seed: {}", value))?;
+            }
+            "--size" => {
+                let value = args.next().ok_or("--size needs a value")?;
+                size = Some(
+                    value
+                        .parse::<usize>()
+                        .map_err(|_| format!("not a size: {}", value))?,
+                );
+            }
+            "-d" | "--data" => {
+                data = Some(DataDir::new(args.next().ok_or("--data needs a value")?));
+            }
+            value => days.push(
+                value
+                    .parse::<u8>()
+                    .map_err(|_| format!("not a day: {}", value))?,
+            ),
+        }
+    }
+
+    if example && generate {
+        return Err("--example and --generate can't both be used".to_string());
+    }
+    let data = data.unwrap_or_else(DataDir::locate);
+    Ok(Options {
+        days,
+        runs,
+        tsv,
+        example,
+        generate,
+        seed,
+        size,
+        data,
+    })
+}
+
+/// The requested days, or every implemented day if none were given
+pub fn select_days(options: &Options) -> Result<Vec<&'static dyn Day>> {
+    crate::select_days(&options.days)
+}
+
+/// Benchmark one day on the input chosen by the options
+pub fn run(options: &Options, day: &dyn Day) -> Result<Vec<Timing>> {
+    if options.generate {
+        let size = options
+            .size
+            .or_else(|| generate::default_size(day.number()))
+            .unwrap_or(0);
+        let raw = generate::generate(day.number(), options.seed, size).ok_or_else(|| {
+            Error::invalid(format!("day {} has no input generator", day.number()))
+        })?;
+        return bench_day(day, raw.as_str(), options.runs);
+    }
+    let kind = if options.example {
+        options
+            .data
+            .examples(day.number())?
+            .first()
+            .map(|v| v.kind)
+            .ok_or_else(|| Error::invalid(format!("day {} has no examples", day.number())))?
+    } else {
+        Kind::Puzzle
+    };
+    let raw = options.data.read_to_string(day.number(), kind)?;
+    bench_day(day, raw.as_str(), options.runs)
+}
+
+/// Entry point for the bench binary, returns the process exit code
+pub fn main() -> i32 {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(msg) => {
+            eprintln!("{}\n{}", msg, USAGE);
+            return 2;
+        }
+    };
+
+    let days = match select_days(&options) {
+        Ok(days) => days,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return 1;
+        }
+    };
+
+    let mut timings = Vec::new();
+    for day in days {
+        let no_examples = || {
+            options
+                .data
+                .examples(day.number())
+                .is_ok_and(|e| e.is_empty())
+        };
+        if options.example && options.days.is_empty() && no_examples() {
+            eprintln!("skipping day {}: no examples", day.number());
+            continue;
+        }
+        match run(&options, day) {
+            Ok(mut day_timings) => timings.append(&mut day_timings),
+            Err(e) => {
+                eprintln!("error: day {}: {}", day.number(), e);
+                return 1;
+            }
+        }
+    }
+
+    let mut out = io::stdout();
+    let written = if options.tsv {
+        write_tsv(&timings, &mut out)
+    } else {
+        write_table(&timings, &mut out)
+    };
+    match written {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn args(values: &[&str]) -> impl Iterator<Item = String> {
+        values
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<String>>()
+            .into_iter()
+    }
+
+    fn options_for(values: &[&str]) -> Options {
+        parse_args(args(values)).unwrap()
+    }
+
+    #[test]
+    fn test_stats() {
+        assert_eq!(None, Stats::from_samples(&[]));
+
+        let samples = [5, 1, 4].map(Duration::from_nanos);
+        assert_matches!(Stats::from_samples(&samples), Some(stats) => {
+            assert_eq!(3, stats.runs);
+            assert_eq!(Duration::from_nanos(1), stats.min);
+            assert_eq!(Duration::from_nanos(4), stats.median);
+            assert_eq!(Duration::from_nanos(5), stats.max);
+        });
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_matches!(parse_args(args(&[])), Ok(options) => {
+            assert!(options.days.is_empty());
+            assert_eq!(10, options.runs);
+            assert!(!options.tsv);
+            assert!(!options.example);
+            assert!(!options.generate);
+        });
+
+        assert_matches!(parse_args(args(&["--runs", "3", "--tsv", "--example", "1", "12"])), Ok(options) => {
+            assert_eq!(vec![1, 12], options.days);
+            assert_eq!(3, options.runs);
+            assert!(options.tsv);
+            assert!(options.example);
+        });
+
+        assert_matches!(parse_args(args(&["--generate", "--seed", "7", "--size", "20", "4"])), Ok(options) => {
+            assert!(options.generate);
+            assert_eq!(7, options.seed);
+            assert_eq!(Some(20), options.size);
+        });
+
+        assert!(parse_args(args(&["--example", "--generate"])).is_err());
+        assert!(parse_args(args(&["--seed", "x"])).is_err());
+        assert!(parse_args(args(&["--runs", "0"])).is_err());
+        assert!(parse_args(args(&["--runs"])).is_err());
+        assert!(parse_args(args(&["twelve"])).is_err());
+    }
+
+    #[test]
+    fn test_run() {
+        let options = parse_args(args(&["-n", "3", "--example", "1", "12"])).unwrap();
+        let timings: Vec<Timing> = select_days(&options)
+            .unwrap()
+            .into_iter()
+            .flat_map(|day| run(&options, day).unwrap())
+            .collect();
+
+        let stages: Vec<(u8, Stage)> = timings.iter().map(|t| (t.day, t.stage)).collect();
+        assert_eq!(
+            vec![
+                (1, Stage::Parse),
+                (1, Stage::Solve(Part::One)),
+                (1, Stage::Solve(Part::Two)),
+                (12, Stage::Parse),
+                (12, Stage::Solve(Part::One)),
+                (12, Stage::Solve(Part::Two)),
+            ],
+            stages
+        );
+        assert!(timings.iter().all(|t| t.stats.runs == 3));
+        assert!(timings
+            .iter()
+            .all(|t| t.stats.min <= t.stats.median && t.stats.median <= t.stats.max));
+
+        let mut out = Vec::new();
+        write_tsv(&timings, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(7, lines.len());
+        if alloc_stats::ENABLED {
+            assert_eq!(
+                "day\tstage\truns\tmin_ns\tmedian_ns\tmax_ns\tallocs\tbytes\tpeak_bytes",
+                lines[0]
+            );
+            // day 1 parses into a vec
+            assert!(timings[0].allocs.unwrap().allocations > 0);
+        } else {
+            assert_eq!("day\tstage\truns\tmin_ns\tmedian_ns\tmax_ns", lines[0]);
+            assert!(timings.iter().all(|t| t.allocs.is_none()));
+        }
+        assert!(lines[1].starts_with("1\tparse\t3\t"));
+        assert!(lines[6].starts_with("12\tpart2\t3\t"));
+
+        assert_eq!(
+            crate::DAYS.len(),
+            select_days(&options_for(&[])).unwrap().len()
+        );
+        assert!(select_days(&options_for(&["26"])).is_err());
+        assert_matches!(
+            run(&options_for(&["-d", "/not/exist"]), crate::day(1).unwrap()),
+            Err(Error::Io { .. })
+        );
+    }
+
+    #[test]
+    fn test_generated() {
+        let options = options_for(&["-n", "2", "--generate", "--size", "5", "4"]);
+        let timings = run(&options, crate::day(4).unwrap()).unwrap();
+        assert_eq!(3, timings.len());
+        assert!(timings.iter().all(|t| t.stats.runs == 2));
+    }
+
+    #[test]
+    fn test_bad_input() {
+        let day = crate::day(1).unwrap();
+        assert_matches!(bench_day(day, "1\nx", 2), Err(Error::Parse { line: 2, .. }));
+    }
+}
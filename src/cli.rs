@@ -0,0 +1,486 @@
+use crate::alloc_stats;
+use crate::answers::{Answers, Verdict, ANSWERS_FILE};
+use crate::error::{Error, Result};
+use crate::report::Value;
+use crate::solution::Part;
+use crate::util::{DataDir, Kind};
+use std::fs;
+use std::io;
+use std::io::prelude::*;
+use std::path::Path;
+
+pub const USAGE: &str =
+    "usage: aoc2021 [--part 1|2|both] [--input PATH|-] [--data DIR] [--json] [--check] [--allocs] [--log FILTER|-v] DAY";
+
+/// where the puzzle input should be read from
+#[derive(Debug, PartialEq, Eq)]
+pub enum Input {
+    /// day{N}.txt in the data directory
+    Default,
+    Stdin,
+    File(String),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Options {
+    pub day: u8,
+    pub parts: Vec<Part>,
+    pub input: Input,
+    pub data: DataDir,
+    /// print a JSON document with the intermediate values
+    pub json: bool,
+    /// report problems with the input instead of solving it
+    pub check: bool,
+    /// show what parsing and solving each part allocated
+    pub allocs: bool,
+    /// overrides $AOC_LOG, e.g. "debug" or "day17=trace"
+    pub log: Option<String>,
+}
+
+fn parse_parts(value: &str) -> std::result::Result<Vec<Part>, String> {
+    match value {
+        "1" => Ok(vec![Part::One]),
+        "2" => Ok(vec![Part::Two]),
+        "both" => Ok(vec![Part::One, Part::Two]),
+        _ => Err(format!("unknown part: {}", value)),
+    }
+}
+
+/// Parse the command line arguments (without the program name)
+/// ```
+/// use aoc2021::cli::{self, Input};
+/// use aoc2021::solution::Part;
+///
+/// let args = ["--part", "2", "--input", "-", "17"].iter().map(|s| s.to_string());
+/// let options = cli::parse_args(args).unwrap();
+///
+/// assert_eq!(17, options.day);
+/// assert_eq!(vec![Part::Two], options.parts);
+/// assert_eq!(Input::Stdin, options.input);
+/// ```
+pub fn parse_args(args: impl Iterator<Item = String>) -> std::result::Result<Options, String> {
+    let mut day = None;
+    let mut parts = vec![Part::One, Part::Two];
+    let mut input = Input::Default;
+    let mut data = None;
+    let mut json = false;
+    let mut check = false;
+    let mut allocs = false;
+    let mut log = None;
+
+    let mut args = args;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-p" | "--part" => {
+                let value = args.next().ok_or("--part needs a value")?;
+                parts = parse_parts(value.as_str())?;
+            }
+            "-i" | "--input" => {
+                input = match args.next().ok_or("--input needs a value")?.as_str() {
+                    "-" => Input::Stdin,
+                    path => Input::File(path.to_string()),
+                };
+            }
+            "-d" | "--data" => {
+                data = Some(DataDir::new(args.next().ok_or("--data needs a value")?));
+            }
+            "--json" => json = true,
+            "--check" => check = true,
+            "--allocs" if !alloc_stats::ENABLED => {
+                return Err("--allocs needs the alloc-stats feature".to_string())
+            }
+            "--allocs" => allocs = true,
+            "-v" | "--verbose" => log = Some("debug".to_string()),
+            "--log" => {
+                let value = args.next().ok_or("--log needs a value")?;
+                crate::log::Filter::parse(value.as_str())?;
+                log = Some(value);
+            }
+            value if day.is_none() => {
+                day = Some(
+                    value
+                        .parse::<u8>()
+                        .map_err(|_| format!("not a day: {}", value))?,
+                );
+            }
+            value => return Err(format!("unexpected argument: {}", value)),
+        }
+    }
+
+    let day = day.ok_or("no day given")?;
+    let data = data.unwrap_or_else(DataDir::locate);
+    Ok(Options {
+        day,
+        parts,
+        input,
+        data,
+        json,
+        check,
+        allocs,
+        log,
+    })
+}
+
+fn read_input(options: &Options) -> Result<String> {
+    match &options.input {
+        Input::Default => options.data.read_to_string(options.day, Kind::Puzzle),
+        Input::File(path) => fs::read_to_string(path).map_err(|e| Error::io(path, e)),
+        Input::Stdin => {
+            let mut raw = String::new();
+            io::stdin()
+                .read_to_string(&mut raw)
+                .map_err(|e| Error::io("stdin", e))?;
+            Ok(raw)
+        }
+    }
+}
+
+impl Options {
+    /// the file name used to look up recorded answers, if there is one
+    fn input_name(&self) -> Option<String> {
+        match &self.input {
+            Input::Default => Some(format!("day{}.txt", self.day)),
+            Input::File(path) => Path::new(path)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string()),
+            Input::Stdin => None,
+        }
+    }
+}
+
+/// Solve the requested parts of a day, writing one line per answer along
+/// with how it compares to the recorded answer, or a single JSON document:
+///
+/// ```text
+/// {"day":2,"results":[{"part":1,"answer":"150","verdict":null,
+///   "fields":{"depth":10,"horiz":15,"distance":150}}, ...]}
+/// ```
+///
+/// With `--allocs` a line for the parse comes first and each part's line ends
+/// with what the part allocated, not counting the parse. In JSON these are a
+/// "parse_allocations" object and an "allocations" object in each result.
+pub fn run(options: &Options, raw: &str, out: &mut impl Write) -> Result<()> {
+    let day = crate::day(options.day)
+        .ok_or_else(|| Error::invalid(format!("day {} is not implemented", options.day)))?;
+    let answers = Answers::load(&options.data)?;
+    let input_name = options.input_name();
+
+    // each part takes its own parsed input, but only the first parse is measured
+    let (parsed, parse_allocs) = alloc_stats::measure(|| day.parse(raw));
+    let mut parsed = Some(parsed?);
+    let parse_allocs = parse_allocs.filter(|_| options.allocs);
+    if let (Some(allocs), false) = (parse_allocs, options.json) {
+        writeln!(out, "day {} parse [{}]", options.day, allocs)
+            .map_err(|e| Error::io("output", e))?;
+    }
+
+    let mut failures = Vec::new();
+    let mut results = Vec::new();
+    for part in options.parts.iter() {
+        let input = match parsed.take() {
+            Some(input) => input,
+            None => day.parse(raw)?,
+        };
+        let (report, allocs) = alloc_stats::measure(|| day.report(input, *part));
+        let report = report?;
+        let allocs = allocs.filter(|_| options.allocs);
+        let answer = report.answer.as_str();
+
+        let verdict = match &input_name {
+            Some(name) => answers.check(options.day, *part, name, answer),
+            None => Verdict::Unknown,
+        };
+        let mut line = match verdict {
+            Verdict::Unknown => format!("day {} part {}: {}", options.day, part.number(), answer),
+            _ => format!(
+                "day {} part {}: {} ({})",
+                options.day,
+                part.number(),
+                answer,
+                verdict
+            ),
+        };
+        if verdict.is_failure() {
+            failures.push(line.clone());
+        }
+        if let Some(allocs) = allocs {
+            line = format!("{} [{}]", line, allocs);
+        }
+
+        if options.json {
+            let mut result = Value::object()
+                .with("part", part.number())
+                .with("answer", answer)
+                .with(
+                    "verdict",
+                    match verdict {
+                        Verdict::Unknown => None,
+                        v => Some(v.to_string()),
+                    },
+                )
+                .with("fields", report.fields());
+            if let Some(allocs) = allocs {
+                result = result.with("allocations", allocations(allocs));
+            }
+            results.push(result);
+        } else {
+            writeln!(out, "{}", line).map_err(|e| Error::io("output", e))?;
+        }
+    }
+
+    if options.json {
+        let mut document = Value::object().with("day", options.day);
+        if let Some(allocs) = parse_allocs {
+            document = document.with("parse_allocations", allocations(allocs));
+        }
+        let document = document.with("results", results);
+        writeln!(out, "{}", document).map_err(|e| Error::io("output", e))?;
+    }
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::invalid(format!(
+            "answers do not match {}: {}",
+            ANSWERS_FILE,
+            failures.join(", ")
+        )))
+    }
+}
+
+fn allocations(allocs: alloc_stats::AllocStats) -> Value {
+    Value::object()
+        .with("allocations", allocs.allocations)
+        .with("bytes", allocs.bytes)
+        .with("peak", allocs.peak)
+}
+
+/// Write every problem with the input, one per line, without solving it.
+/// Returns whether the input is clean.
+pub fn check(options: &Options, raw: &str, out: &mut impl Write) -> Result<bool> {
+    let day = crate::day(options.day)
+        .ok_or_else(|| Error::invalid(format!("day {} is not implemented", options.day)))?;
+    let name = options.input_name().unwrap_or_else(|| "stdin".to_string());
+
+    let problems = day.validate(raw);
+    for problem in problems.iter() {
+        writeln!(out, "{}: {}", name, problem).map_err(|e| Error::io("output", e))?;
+    }
+    Ok(problems.is_empty())
+}
+
+/// Entry point for the binary, returns the process exit code
+pub fn main() -> i32 {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(msg) => {
+            eprintln!("{}\n{}", msg, USAGE);
+            return 2;
+        }
+    };
+
+    if let Some(filter) = &options.log {
+        crate::log::init(filter.as_str()).expect("filter was checked by parse_args");
+    }
+
+    if options.check {
+        return match read_input(&options).and_then(|raw| check(&options, &raw, &mut io::stdout())) {
+            Ok(true) => 0,
+            Ok(false) => 1,
+            Err(e) => {
+                eprintln!("error: day {}: {}", options.day, e);
+                1
+            }
+        };
+    }
+
+    let result = read_input(&options).and_then(|raw| run(&options, &raw, &mut io::stdout()));
+
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("error: day {}: {}", options.day, e);
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn args(values: &[&str]) -> impl Iterator<Item = String> {
+        values
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<String>>()
+            .into_iter()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_matches!(parse_args(args(&["5"])), Ok(options) => {
+            assert_eq!(5, options.day);
+            assert_eq!(vec![Part::One, Part::Two], options.parts);
+            assert_eq!(Input::Default, options.input);
+        });
+
+        assert_matches!(parse_args(args(&["-i", "data/day4_test.txt", "-p", "1", "4"])), Ok(options) => {
+            assert_eq!(4, options.day);
+            assert_eq!(vec![Part::One], options.parts);
+            assert_eq!(Input::File("data/day4_test.txt".to_string()), options.input);
+        });
+
+        assert_matches!(parse_args(args(&["--data", "/tmp/aoc", "3"])), Ok(options) => {
+            assert_eq!(DataDir::new("/tmp/aoc"), options.data);
+            assert!(!options.json);
+        });
+        assert!(parse_args(args(&["--json", "3"])).unwrap().json);
+        assert!(parse_args(args(&["--check", "3"])).unwrap().check);
+        assert_eq!(
+            alloc_stats::ENABLED,
+            parse_args(args(&["--allocs", "3"])).is_ok_and(|o| o.allocs)
+        );
+        assert_eq!(None, parse_args(args(&["3"])).unwrap().log);
+        assert_eq!(
+            Some("debug".to_string()),
+            parse_args(args(&["-v", "3"])).unwrap().log
+        );
+        assert_eq!(
+            Some("day3=trace".to_string()),
+            parse_args(args(&["--log", "day3=trace", "3"])).unwrap().log
+        );
+        assert!(parse_args(args(&["--log", "loud", "3"])).is_err());
+
+        assert!(parse_args(args(&[])).is_err());
+        assert!(parse_args(args(&["five"])).is_err());
+        assert!(parse_args(args(&["5", "6"])).is_err());
+        assert!(parse_args(args(&["--part", "3", "5"])).is_err());
+        assert!(parse_args(args(&["5", "--input"])).is_err());
+    }
+
+    #[test]
+    fn test_run() {
+        // nothing is recorded for stdin
+        let options = parse_args(args(&["-i", "-", "5"])).unwrap();
+        let raw = fs::read_to_string("data/day5_test.txt").unwrap();
+
+        let mut out = Vec::new();
+        run(&options, &raw, &mut out).expect("day 5 runs");
+        assert_eq!(
+            "day 5 part 1: 5\nday 5 part 2: 12\n",
+            String::from_utf8(out).unwrap()
+        );
+
+        let options = parse_args(args(&["-i", "data/day5_test.txt", "5"])).unwrap();
+        let mut out = Vec::new();
+        run(&options, &raw, &mut out).expect("day 5 runs");
+        assert_eq!(
+            "day 5 part 1: 5 (correct)\nday 5 part 2: 12 (correct)\n",
+            String::from_utf8(out).unwrap()
+        );
+
+        // the example is not the puzzle input
+        let options = parse_args(args(&["-i", "data/day5.txt", "-p", "1", "5"])).unwrap();
+        let mut out = Vec::new();
+        assert_matches!(run(&options, &raw, &mut out), Err(Error::InvalidState(_)));
+        assert_eq!(
+            "day 5 part 1: 5 (wrong, expected 5280)\n",
+            String::from_utf8(out).unwrap()
+        );
+
+        let options = parse_args(args(&["--json", "-i", "data/day2_test.txt", "2"])).unwrap();
+        let raw = fs::read_to_string("data/day2_test.txt").unwrap();
+        let mut out = Vec::new();
+        run(&options, &raw, &mut out).expect("day 2 runs");
+        assert_eq!(
+            concat!(
+                r#"{"day":2,"results":["#,
+                r#"{"part":1,"answer":"150","verdict":"correct","fields":{"depth":10,"horiz":15,"distance":150}},"#,
+                r#"{"part":2,"answer":"900","verdict":"correct","fields":{"depth":60,"horiz":15,"distance":900,"aim":10}}"#,
+                "]}\n"
+            ),
+            String::from_utf8(out).unwrap()
+        );
+
+        let mut out = Vec::new();
+        assert_matches!(
+            run(&options, "nothing", &mut out),
+            Err(Error::Parse { line: 1, .. })
+        );
+
+        let options = parse_args(args(&["26"])).unwrap();
+        assert_matches!(run(&options, &raw, &mut out), Err(Error::InvalidState(_)));
+    }
+
+    #[test]
+    fn test_run_allocs() {
+        if !alloc_stats::ENABLED {
+            return;
+        }
+        let options = parse_args(args(&["--allocs", "-i", "-", "-p", "1", "5"])).unwrap();
+        let raw = fs::read_to_string("data/day5_test.txt").unwrap();
+        let mut out = Vec::new();
+        run(&options, &raw, &mut out).expect("day 5 runs");
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(2, lines.len(), "{}", out);
+        assert!(lines[0].starts_with("day 5 parse ["), "{}", out);
+        assert!(lines[1].starts_with("day 5 part 1: 5 ["), "{}", out);
+        assert!(out.ends_with(" bytes]\n"), "{}", out);
+
+        // the parse isn't counted again in the parts
+        let stats = |line: &str| {
+            let (_, stats) = line.split_once('[').unwrap();
+            stats.split(' ').next().unwrap().parse::<u64>().unwrap()
+        };
+        let options = parse_args(args(&["--allocs", "-i", "-", "5"])).unwrap();
+        let mut out = Vec::new();
+        run(&options, &raw, &mut out).expect("day 5 runs");
+        let out = String::from_utf8(out).unwrap();
+        let counts: Vec<u64> = out.lines().map(stats).collect();
+        let parse = alloc_stats::measure(|| crate::day(5).unwrap().parse(&raw)).1;
+        assert_eq!(parse.unwrap().allocations, counts[0], "{}", out);
+        assert_eq!(3, counts.len(), "{}", out);
+
+        let options = parse_args(args(&["--allocs", "--json", "-i", "-", "-p", "1", "5"])).unwrap();
+        let mut out = Vec::new();
+        run(&options, &raw, &mut out).expect("day 5 runs");
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains(r#","allocations":{"allocations":"#), "{}", out);
+        assert!(
+            out.starts_with(r#"{"day":5,"parse_allocations":{"allocations":"#),
+            "{}",
+            out
+        );
+    }
+
+    #[test]
+    fn test_check() {
+        let options = parse_args(args(&["--check", "-i", "data/day4_test.txt", "4"])).unwrap();
+        let raw = fs::read_to_string("data/day4_test.txt").unwrap();
+        let mut out = Vec::new();
+        assert!(check(&options, &raw, &mut out).unwrap());
+        assert!(out.is_empty());
+
+        let options = parse_args(args(&["--check", "-i", "-", "1"])).unwrap();
+        let mut out = Vec::new();
+        assert!(!check(
+            &options,
+            "199
+2o0
+208
+-
+",
+            &mut out
+        )
+        .unwrap());
+        assert_eq!(
+            "stdin: line 2, column 1: not a number: 2o0
+stdin: line 4, column 1: not a number: -
+",
+            String::from_utf8(out).unwrap()
+        );
+    }
+}
@@ -1,21 +1,57 @@
-pub fn larger_measurements(input: impl Iterator<Item = i32>) -> u32 {
-    let mut prev: Option<i32> = None;
-    let mut count: u32 = 0;
-    for value in input {
-        if let Some(previous) = prev {
+pub mod analytics;
+
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+use crate::stream::Streaming;
+use crate::util::SlidingWindows;
+
+/// Counts the measurements larger than the one before, one at a time
+#[derive(Clone, Debug, Default)]
+pub struct Increases {
+    prev: Option<i32>,
+    count: u32,
+}
+
+impl Increases {
+    pub fn add(&mut self, value: i32) {
+        if let Some(previous) = self.prev {
             if value > previous {
-                count += 1;
+                self.count += 1;
             }
         }
-        prev = Some(value);
+        self.prev = Some(value);
     }
-    count
+}
+
+impl Streaming for Increases {
+    type Answer = u32;
+
+    fn push(&mut self, line: &str) -> Result<()> {
+        self.add(super::util::parse_int(line.to_string())?);
+        Ok(())
+    }
+
+    fn answer(&self) -> u32 {
+        self.count
+    }
+}
+
+pub fn larger_measurements(input: impl Iterator<Item = i32>) -> u32 {
+    let mut increases = Increases::default();
+    input.for_each(|value| increases.add(value));
+    increases.answer()
 }
 
 pub fn larger_triples(input: impl Iterator<Item = i32>) -> u32 {
+    larger_windows(input, 3)
+}
+
+/// How often the sum of a window of `size` measurements is larger than the
+/// one before it
+pub fn larger_windows(input: impl Iterator<Item = i32>, size: usize) -> u32 {
     let mut prev: Option<i32> = None;
     let mut count: u32 = 0;
-    for value in super::util::TripleIter::new(Box::new(input)) {
+    for value in input.sliding_sum(size) {
         if let Some(previous) = prev {
             if value > previous {
                 count += 1;
@@ -26,22 +62,64 @@ pub fn larger_triples(input: impl Iterator<Item = i32>) -> u32 {
     count
 }
 
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u8 = 1;
+
+    type Input = Vec<i32>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(raw: &str) -> Result<Self::Input> {
+        super::util::parse_lines(raw, super::util::parse_int)
+    }
+
+    fn validate(raw: &str) -> Vec<Error> {
+        super::util::check_lines(raw, super::util::parse_int)
+    }
+
+    fn part1(input: Self::Input) -> Result<Self::Answer1> {
+        Ok(larger_measurements(input.into_iter()))
+    }
+
+    fn part2(input: Self::Input) -> Result<Self::Answer2> {
+        Ok(larger_triples(input.into_iter()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::answers::verify;
+    use crate::solution::Part;
 
-    // fn test_data() -> Vec<i32> {
-    //     let data: Vec<i32> = vec![199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
-    //     data
-    // }
+    fn inline_data() -> impl Iterator<Item = i32> {
+        let data = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263";
+        super::super::util::read_ints_from_reader(data.as_bytes()).expect("inline data")
+    }
 
     fn test_data() -> impl Iterator<Item = i32> {
-        super::super::util::read_ints_from_file("data/day1_test.txt")
+        super::super::util::read_ints_from_file("data/day1_test.txt").expect("test data")
     }
 
     fn puzzle_input() -> impl Iterator<Item = i32> {
-        super::super::util::read_ints_from_file("data/day1.txt")
+        super::super::util::read_ints_from_file("data/day1.txt").expect("puzzle input")
+    }
+
+    #[test]
+    fn test_inline_data() {
+        assert_eq!(7, larger_measurements(inline_data()));
+        assert_eq!(5, larger_triples(inline_data()));
+    }
+
+    #[test]
+    fn test_larger_windows() {
+        // a window of one is just the measurements
+        assert_eq!(7, larger_windows(inline_data(), 1));
+        assert_eq!(5, larger_windows(inline_data(), 3));
+        assert_eq!(0, larger_windows(inline_data(), 10));
     }
 
     #[test]
@@ -56,7 +134,7 @@ mod tests {
 
         let result = larger_measurements(puzzle_input());
 
-        println!("day 1 part 1 result: {}", result)
+        verify(1, Part::One, "day1.txt", result);
     }
 
     #[test]
@@ -67,6 +145,6 @@ mod tests {
 
         let result = larger_triples(puzzle_input());
 
-        println!("day 1 part 2 result: {}", result)
+        verify(1, Part::Two, "day1.txt", result);
     }
 }
@@ -0,0 +1,251 @@
+//! A closer look at a series of depth measurements than counting how often
+//! they increase.
+
+/// A stretch of measurements that only go one way
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Run {
+    /// index of the first measurement in the run
+    pub start: usize,
+    /// how many measurements are in it
+    pub len: usize,
+}
+
+impl Run {
+    /// index just past the last measurement
+    pub fn end(&self) -> usize {
+        self.start + self.len
+    }
+}
+
+/// How the sums of consecutive windows of `size` measurements compare
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WindowDeltas {
+    pub size: usize,
+    /// `deltas[i]` is the sum of the window starting at `i + 1` less the one
+    /// starting at `i`
+    pub deltas: Vec<i64>,
+    /// how many of the deltas are increases
+    pub increases: usize,
+}
+
+/// A measurement far from the ones just before it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Outlier {
+    pub index: usize,
+    pub value: i32,
+    /// the mean of the measurements before it
+    pub baseline: f64,
+    /// how many standard deviations of those measurements it is away from
+    /// the baseline, negative when shallower
+    pub deviation: f64,
+}
+
+/// Everything but the outliers, which need their own settings
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Analysis {
+    pub increases: Vec<usize>,
+    pub longest_increasing: Option<Run>,
+    pub longest_decreasing: Option<Run>,
+    pub windows: Vec<WindowDeltas>,
+}
+
+/// Analyse the depths, with the deltas for each of the window sizes
+/// ```
+/// use aoc2021::day1::analytics::{self, Run};
+///
+/// let depths = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+/// let analysis = analytics::analyse(&depths, &[1, 3]);
+/// assert_eq!(7, analysis.increases.len());
+/// assert_eq!(Some(Run { start: 0, len: 4 }), analysis.longest_increasing);
+/// assert_eq!(vec![7, 5], analysis.windows.iter().map(|w| w.increases).collect::<Vec<_>>());
+/// ```
+pub fn analyse(depths: &[i32], window_sizes: &[usize]) -> Analysis {
+    Analysis {
+        increases: increases(depths),
+        longest_increasing: longest_increasing(depths),
+        longest_decreasing: longest_decreasing(depths),
+        windows: window_sizes
+            .iter()
+            .map(|size| window_deltas(depths, *size))
+            .collect(),
+    }
+}
+
+/// the index of every measurement deeper than the one before it
+pub fn increases(depths: &[i32]) -> Vec<usize> {
+    (1..depths.len())
+        .filter(|i| depths[*i] > depths[i - 1])
+        .collect()
+}
+
+/// The first of the longest runs where each measurement is `further` than
+/// the one before. A single measurement is a run of one.
+fn longest_run(depths: &[i32], further: fn(i32, i32) -> bool) -> Option<Run> {
+    let mut longest: Option<Run> = None;
+    let mut start = 0;
+    for i in 0..depths.len() {
+        if i > 0 && !further(depths[i - 1], depths[i]) {
+            start = i;
+        }
+        let run = Run {
+            start,
+            len: i + 1 - start,
+        };
+        if longest.is_none_or(|l| run.len > l.len) {
+            longest = Some(run);
+        }
+    }
+    longest
+}
+
+pub fn longest_increasing(depths: &[i32]) -> Option<Run> {
+    longest_run(depths, |a, b| b > a)
+}
+
+pub fn longest_decreasing(depths: &[i32]) -> Option<Run> {
+    longest_run(depths, |a, b| b < a)
+}
+
+/// Consecutive windows share all but their first and last measurements, so
+/// the difference between their sums is just `a[i + size] - a[i]`, and the
+/// sums themselves are never needed.
+pub fn window_deltas(depths: &[i32], size: usize) -> WindowDeltas {
+    let deltas: Vec<i64> = if size == 0 {
+        Vec::new()
+    } else {
+        depths
+            .iter()
+            .zip(depths.iter().skip(size))
+            .map(|(first, next)| *next as i64 - *first as i64)
+            .collect()
+    };
+    WindowDeltas {
+        size,
+        increases: deltas.iter().filter(|d| **d > 0).count(),
+        deltas,
+    }
+}
+
+/// The measurements more than `threshold` standard deviations away from the
+/// mean of the `window` measurements before them. A jump after a perfectly
+/// flat stretch is always an outlier.
+pub fn outliers(depths: &[i32], window: usize, threshold: f64) -> Vec<Outlier> {
+    let mut found = Vec::new();
+    if window == 0 {
+        return found;
+    }
+    // running sums over the window, exact in integers
+    let (mut sum, mut squares) = (0i64, 0i128);
+    for (i, value) in depths.iter().enumerate() {
+        if i >= window {
+            let n = window as f64;
+            let baseline = sum as f64 / n;
+            let variance = (squares as f64 / n - baseline * baseline).max(0.0);
+            let distance = *value as f64 - baseline;
+            let deviation = match variance.sqrt() {
+                spread if spread > 0.0 => distance / spread,
+                _ if distance == 0.0 => 0.0,
+                _ => f64::INFINITY.copysign(distance),
+            };
+            if deviation.abs() > threshold {
+                found.push(Outlier {
+                    index: i,
+                    value: *value,
+                    baseline,
+                    deviation,
+                });
+            }
+
+            let leaving = depths[i - window] as i64;
+            sum -= leaving;
+            squares -= (leaving * leaving) as i128;
+        }
+        sum += *value as i64;
+        squares += (*value as i64 * *value as i64) as i128;
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const DEPTHS: [i32; 10] = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+
+    #[test]
+    fn test_increases() {
+        assert_eq!(vec![1, 2, 3, 5, 6, 7, 9], increases(&DEPTHS));
+        assert!(increases(&[]).is_empty());
+        assert!(increases(&[3, 3, 2]).is_empty());
+    }
+
+    #[test]
+    fn test_runs() {
+        // 200 207 240 269 is as long, but comes later
+        assert_eq!(Some(Run { start: 0, len: 4 }), longest_increasing(&DEPTHS));
+        assert_eq!(Some(Run { start: 3, len: 2 }), longest_decreasing(&DEPTHS));
+        assert_eq!(4, longest_increasing(&DEPTHS).unwrap().end());
+
+        assert_eq!(None, longest_increasing(&[]));
+        // equal measurements break a run
+        assert_eq!(
+            Some(Run { start: 0, len: 1 }),
+            longest_increasing(&[5, 5, 5])
+        );
+        assert_eq!(
+            Some(Run { start: 2, len: 3 }),
+            longest_decreasing(&[1, 2, 9, 5, 1, 1])
+        );
+    }
+
+    #[test]
+    fn test_window_deltas() {
+        let w = window_deltas(&DEPTHS, 3);
+        assert_eq!(vec![11, 0, -1, 30, 69, 53, 23], w.deltas);
+        assert_eq!(5, w.increases);
+
+        // the deltas are the differences between the window sums
+        let sums: Vec<i64> = DEPTHS
+            .windows(3)
+            .map(|w| w.iter().map(|d| *d as i64).sum())
+            .collect();
+        let differences: Vec<i64> = sums.windows(2).map(|s| s[1] - s[0]).collect();
+        assert_eq!(differences, w.deltas);
+
+        for size in 1..=11 {
+            assert_eq!(
+                super::super::larger_windows(DEPTHS.into_iter(), size) as usize,
+                window_deltas(&DEPTHS, size).increases
+            );
+        }
+        assert!(window_deltas(&DEPTHS, 0).deltas.is_empty());
+        // no overflow at the extremes
+        assert_eq!(
+            vec![u32::MAX as i64],
+            window_deltas(&[i32::MIN, i32::MAX], 1).deltas
+        );
+    }
+
+    #[test]
+    fn test_outliers() {
+        let found = outliers(&DEPTHS, 3, 3.0);
+        assert_eq!(
+            vec![6, 7],
+            found.iter().map(|o| o.index).collect::<Vec<_>>()
+        );
+        assert_eq!(240, found[0].value);
+        assert!((found[0].baseline - 617.0 / 3.0).abs() < 1e-9);
+        assert!(found[0].deviation > 8.0 && found[1].deviation < 3.1);
+
+        // nothing stands out from a steady series
+        assert!(outliers(&[100, 100, 100, 100], 2, 0.5).is_empty());
+        // however small the jump after a flat stretch
+        assert_matches!(outliers(&[100, 100, 100, 99], 3, 10.0).as_slice(), [o] => {
+            assert_eq!(3, o.index);
+            assert_eq!(f64::NEG_INFINITY, o.deviation);
+        });
+        assert!(outliers(&DEPTHS, 0, 1.0).is_empty());
+        assert!(outliers(&DEPTHS, 10, 1.0).is_empty());
+    }
+}
@@ -1,10 +1,26 @@
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+use crate::stream::Streaming;
 use std::collections::HashMap;
 type CalculationInput = String;
 type DayResult = u64;
 
-pub fn part1(_input: impl Iterator<Item = CalculationInput>) -> DayResult {
+fn unexpected_char(c: char) -> Error {
+    Error::invalid(format!("not a bracket: {}", c))
+}
+
+pub fn part1(_input: impl Iterator<Item = CalculationInput>) -> Result<DayResult> {
+    let mut score = 0;
+    for line in _input {
+        score += syntax_error_score(&line)?;
+    }
+    Ok(score)
+}
+
+/// What the closing characters that don't match cost in one line
+fn syntax_error_score(line: &str) -> Result<DayResult> {
     let mut parser = Vec::new();
-    let mut invalid = HashMap::new();
+    let mut score = 0;
 
     let open_close: HashMap<char, char> = [('(', ')'), ('[', ']'), ('{', '}'), ('<', '>')]
         .iter()
@@ -13,52 +29,67 @@ pub fn part1(_input: impl Iterator<Item = CalculationInput>) -> DayResult {
 
     let close_open: HashMap<char, char> = open_close.iter().map(|(k, v)| (*v, *k)).collect();
 
-    for line in _input {
-        parser.clear();
-
-        'parsing: for next_char in line.chars() {
-            match next_char {
-                c if open_close.contains_key(&c) => parser.push(c),
-                _ => {
-                    // must be a close char
-                    if let Some(last_char) = parser.pop() {
-                        // what is the open for this char
-                        let open_char = *close_open
-                            .get(&next_char)
-                            .expect("if we are not an open, then we must be a close");
-
-                        if open_char == last_char {
-                            // next_char closes last_char, so this is good
-                            continue 'parsing;
-                        }
-                        // this string is bad, fall through to next line
-                    }
-                    // else we had no chars, so it's bad
-
-                    if let Some(v) = invalid.remove(&next_char) {
-                        invalid.insert(next_char, v + 1);
-                    } else {
-                        invalid.insert(next_char, 1);
+    'parsing: for next_char in line.chars() {
+        match next_char {
+            c if open_close.contains_key(&c) => parser.push(c),
+            _ => {
+                // must be a close char, so what is the open for this char
+                let open_char = *close_open
+                    .get(&next_char)
+                    .ok_or_else(|| unexpected_char(next_char))?;
+
+                if let Some(last_char) = parser.pop() {
+                    if open_char == last_char {
+                        // next_char closes last_char, so this is good
+                        continue 'parsing;
                     }
+                    // this string is bad, fall through to next line
                 }
+                // else we had no chars, so it's bad
+
+                score += match next_char {
+                    ')' => 3,
+                    ']' => 57,
+                    '}' => 1197,
+                    '>' => 25137,
+                    _ => unreachable!(),
+                };
             }
         }
-        // all ok! we ignore this line.
-    }
-
-    invalid.iter().fold(0, |acc, (k, v)| {
-        acc + (match *k {
-            ')' => 3,
-            ']' => 57,
-            '}' => 1197,
-            '>' => 25137,
-            _ => unreachable!(),
-        } * *v)
-    })
+    }
+    // all ok! we ignore this line.
+    Ok(score)
+}
+
+fn check_brackets(line: &str) -> Result<()> {
+    match line.chars().position(|c| !"()[]{}<>".contains(c)) {
+        Some(i) => Err(Error::parse_line(i + 1, "expected a bracket")),
+        None => Ok(()),
+    }
+}
+
+/// The syntax error score of the lines so far
+#[derive(Clone, Debug, Default)]
+pub struct SyntaxScore {
+    score: DayResult,
+}
+
+impl Streaming for SyntaxScore {
+    type Answer = DayResult;
+
+    fn push(&mut self, line: &str) -> Result<()> {
+        check_brackets(line)?;
+        self.score += syntax_error_score(line)?;
+        Ok(())
+    }
+
+    fn answer(&self) -> DayResult {
+        self.score
+    }
 }
 
 /// in part two, we want to match on the incomplete lines
-pub fn part2(_input: impl Iterator<Item = CalculationInput>) -> DayResult {
+pub fn part2(_input: impl Iterator<Item = CalculationInput>) -> Result<DayResult> {
     let mut parser = Vec::new();
 
     // immutable hash map
@@ -77,13 +108,12 @@ pub fn part2(_input: impl Iterator<Item = CalculationInput>) -> DayResult {
             match next_char {
                 c if open_close.contains_key(&c) => parser.push(c),
                 _ => {
-                    // must be a close char
-                    if let Some(last_char) = parser.pop() {
-                        // what is the open for this char
-                        let open_char = *close_open
-                            .get(&next_char)
-                            .expect("if we are not an open, then we must be a close");
+                    // must be a close char, so what is the open for this char
+                    let open_char = *close_open
+                        .get(&next_char)
+                        .ok_or_else(|| unexpected_char(next_char))?;
 
+                    if let Some(last_char) = parser.pop() {
                         if open_char == last_char {
                             // next_char closes last_char, so this is good
                             continue 'parsing;
@@ -124,40 +154,95 @@ pub fn part2(_input: impl Iterator<Item = CalculationInput>) -> DayResult {
     }
     // we want the middle score by value
     score.sort_unstable();
-    score[((score.len() - 2) / 2) + 1]
+    if score.is_empty() {
+        return Err(Error::invalid("no incomplete lines"));
+    }
+    Ok(score[score.len() / 2])
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+
+    type Input = Vec<CalculationInput>;
+    type Answer1 = DayResult;
+    type Answer2 = DayResult;
+
+    fn parse(raw: &str) -> Result<Self::Input> {
+        Ok(raw.lines().map(String::from).collect())
+    }
+
+    fn validate(raw: &str) -> Vec<Error> {
+        super::util::check_lines(raw, |line| check_brackets(&line))
+    }
+
+    fn part1(input: Self::Input) -> Result<Self::Answer1> {
+        part1(input.into_iter())
+    }
+
+    fn part2(input: Self::Input) -> Result<Self::Answer2> {
+        part2(input.into_iter())
+    }
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::answers::verify;
+    use crate::solution::Part;
 
     fn test_data() -> impl Iterator<Item = CalculationInput> {
-        super::super::util::read_data(10, true)
+        super::super::util::read_data(10, true).expect("test data")
     }
 
     fn puzzle_input() -> impl Iterator<Item = CalculationInput> {
-        super::super::util::read_data(10, false)
+        super::super::util::read_data(10, false).expect("puzzle input")
     }
 
     #[test]
     fn test_part1() {
-        let result = part1(test_data());
+        let result = part1(test_data()).unwrap();
 
         assert_eq!(26397, result);
 
-        let result = part1(puzzle_input());
-        println!("day 10 part 1: result={}", result);
+        let result = part1(puzzle_input()).unwrap();
+        verify(10, Part::One, "day10.txt", result);
     }
 
     #[test]
     fn test_part2() {
-        let result = part2(test_data());
+        let result = part2(test_data()).unwrap();
 
         assert_eq!(288957, result);
 
-        let result = part2(puzzle_input());
+        let result = part2(puzzle_input()).unwrap();
 
-        println!("day 10 part 2: result={}", result);
+        verify(10, Part::Two, "day10.txt", result);
+    }
+
+    #[test]
+    fn test_bad_input() {
+        let lines = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<String>>();
+
+        assert_matches!(
+            part1(lines(&["(<x>)"]).into_iter()),
+            Err(Error::InvalidState(_))
+        );
+        assert_matches!(
+            part1(lines(&["x"]).into_iter()),
+            Err(Error::InvalidState(_))
+        );
+        assert_matches!(
+            part2(lines(&["(<x>)"]).into_iter()),
+            Err(Error::InvalidState(_))
+        );
+        // only corrupt lines, so there is no middle score
+        assert_matches!(
+            part2(lines(&["(]"]).into_iter()),
+            Err(Error::InvalidState(_))
+        );
+        assert_eq!(1, part2(lines(&["("]).into_iter()).unwrap());
     }
 }
@@ -1,4 +1,8 @@
-type CalculationInput = Vec<u8>;
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+use crate::util::Grid;
+
+type CalculationInput = Grid<u8>;
 type DayResult = u64;
 
 struct Octopus {
@@ -32,64 +36,9 @@ impl Octopus {
     }
 }
 
-fn find_neighbors(all: &[Vec<Octopus>], i: usize, j: usize) -> Vec<(usize, usize)> {
-    let find = |x: usize, y: usize| {
-        if let Some(row) = all.get(x) {
-            if row.get(y).is_some() {
-                return Some((x, y));
-            }
-        }
-        None
-    };
-    let mut bors = Vec::new();
-
-    // usize=0 - 1 will panic
-    if i > 0 && j > 0 {
-        // top left
-        if let Some(neighbor) = find(i - 1, j - 1) {
-            bors.push(neighbor);
-        }
-    }
-
-    if i > 0 {
-        // left
-        if let Some(neighbor) = find(i - 1, j) {
-            bors.push(neighbor);
-        }
-        // bottom left
-        if let Some(neighbor) = find(i - 1, j + 1) {
-            bors.push(neighbor);
-        }
-    }
-    if j > 0 {
-        // right
-        if let Some(neighbor) = find(i, j - 1) {
-            bors.push(neighbor);
-        }
-        // top right
-        if let Some(neighbor) = find(i + 1, j - 1) {
-            bors.push(neighbor);
-        }
-    }
-
-    if let Some(neighbor) = find(i + 1, j) {
-        bors.push(neighbor);
-    }
-    if let Some(neighbor) = find(i, j + 1) {
-        bors.push(neighbor);
-    }
-    if let Some(neighbor) = find(i + 1, j + 1) {
-        bors.push(neighbor);
-    }
-
-    bors
-}
-
-fn do_round(octopi: &mut Vec<Vec<Octopus>>) -> u64 {
-    for pod in octopi.iter_mut() {
-        for octopus in pod.iter_mut() {
-            octopus.incr();
-        }
+fn do_round(octopi: &mut Grid<Octopus>) -> u64 {
+    for octopus in octopi.values_mut() {
+        octopus.incr();
     }
 
     let mut do_run = true;
@@ -97,18 +46,17 @@ fn do_round(octopi: &mut Vec<Vec<Octopus>>) -> u64 {
     while do_run {
         do_run = false;
         let mut to_check = Vec::new();
-        for i in 0..10 {
-            for j in 0..10 {
-                let mut octopus: &mut Octopus = octopi.get_mut(i).unwrap().get_mut(j).unwrap();
-                if octopus.newly_flashing() {
-                    octopus.flash_checked = true;
-                    to_check.push((i, j));
-                }
+        for position in octopi.positions() {
+            let octopus = &mut octopi[position];
+            if octopus.newly_flashing() {
+                octopus.flash_checked = true;
+                to_check.push(position);
             }
         }
-        for (i, j) in to_check.iter() {
-            for (ni, nj) in find_neighbors(octopi, *i, *j) {
-                let neighbor = &mut octopi[ni][nj];
+        for position in to_check {
+            let neighbors: Vec<(usize, usize)> = octopi.neighbors8(position).collect();
+            for n in neighbors {
+                let neighbor = &mut octopi[n];
                 if !neighbor.flash_checked {
                     neighbor.incr();
                     if !do_run {
@@ -122,34 +70,20 @@ fn do_round(octopi: &mut Vec<Vec<Octopus>>) -> u64 {
 
     // we check for flashing at the end of the round,
     // and reset
-    for x in octopi.iter_mut() {
-        for o in x.iter_mut() {
-            if o.is_flashing() {
-                assert!(o.flash_checked);
-                newly_flashing += 1;
-                o.reset();
-            }
+    for o in octopi.values_mut() {
+        if o.is_flashing() {
+            assert!(o.flash_checked);
+            newly_flashing += 1;
+            o.reset();
         }
     }
 
-    // octopi.iter().for_each(|row| {
-    //     println!(
-    //         "{}",
-    //         row.iter().fold(String::new(), |mut acc, v| {
-    //             for c in v.energy.to_string().chars() {
-    //                 acc.push(c);
-    //             }
-    //             acc
-    //         })
-    //     );
-    // });
+    // print!("{}", octopi.render(|o| char::from_digit(o.energy as u32, 10).unwrap_or('*')));
     newly_flashing
 }
 
-pub fn part1(_input: impl Iterator<Item = CalculationInput>) -> DayResult {
-    let mut octopi: Vec<Vec<Octopus>> = _input
-        .map(|v| v.iter().map(Octopus::new).collect())
-        .collect();
+pub fn part1(input: &CalculationInput) -> DayResult {
+    let mut octopi: Grid<Octopus> = input.map(Octopus::new);
 
     let mut flashes: u64 = 0;
 
@@ -174,55 +108,93 @@ pub fn part1(_input: impl Iterator<Item = CalculationInput>) -> DayResult {
 }
 
 /// in part two, we want to match on the incomplete lines
-pub fn part2(_input: impl Iterator<Item = CalculationInput>) -> DayResult {
-    let mut octopi: Vec<Vec<Octopus>> = _input
-        .map(|v| v.iter().map(Octopus::new).collect())
-        .collect();
+pub fn part2(input: &CalculationInput) -> Result<DayResult> {
+    let mut octopi: Grid<Octopus> = input.map(Octopus::new);
 
-    let just_flashed =
-        |oo: &Vec<Vec<Octopus>>| oo.iter().all(|row| row.iter().all(|o| o.energy == 0));
+    let just_flashed = |oo: &Grid<Octopus>| oo.values().all(|o| o.energy == 0);
 
     let mut round: u64 = 0;
     while !just_flashed(&octopi) {
         round += 1;
         do_round(&mut octopi);
-        assert!(round < 250);
+        if round >= 250 {
+            return Err(Error::invalid("octopi did not all flash within 250 rounds"));
+        }
+    }
+
+    Ok(round)
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+
+    type Input = CalculationInput;
+    type Answer1 = DayResult;
+    type Answer2 = DayResult;
+
+    fn parse(raw: &str) -> Result<Self::Input> {
+        Grid::parse_digits(raw)
+    }
+
+    fn validate(raw: &str) -> Vec<Error> {
+        super::util::check_grid(raw, |c| c.is_ascii_digit())
     }
 
-    round
+    fn part1(input: Self::Input) -> Result<Self::Answer1> {
+        Ok(part1(&input))
+    }
+
+    fn part2(input: Self::Input) -> Result<Self::Answer2> {
+        part2(&input)
+    }
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::answers::verify;
+    use crate::solution::Part;
 
-    fn test_data() -> impl Iterator<Item = CalculationInput> {
-        super::super::util::read_int_list_from_file("data/day11_test.txt")
+    fn test_data() -> CalculationInput {
+        super::super::util::read_digit_grid_from_file("data/day11_test.txt").expect("test data")
     }
 
-    fn puzzle_input() -> impl Iterator<Item = CalculationInput> {
-        super::super::util::read_int_list_from_file("data/day11.txt")
+    fn puzzle_input() -> CalculationInput {
+        super::super::util::read_digit_grid_from_file("data/day11.txt").expect("puzzle input")
     }
 
     #[test]
     fn test_part1() {
-        let result = part1(test_data());
+        let result = part1(&test_data());
 
         assert_eq!(1656, result);
 
-        let result = part1(puzzle_input());
-        println!("day 11 part 1: result={}", result);
+        let result = part1(&puzzle_input());
+        verify(11, Part::One, "day11.txt", result);
     }
 
     #[test]
     fn test_part2() {
-        let result = part2(test_data());
+        let result = part2(&test_data()).unwrap();
 
         assert_eq!(195, result);
 
-        let result = part2(puzzle_input());
+        let result = part2(&puzzle_input()).unwrap();
 
-        println!("day 11 part 2: result={}", result);
+        verify(11, Part::Two, "day11.txt", result);
+    }
+
+    #[test]
+    fn test_small_grid() {
+        // grids don't have to be 10x10
+        let input = Grid::new(1, 1, 5);
+        assert_eq!(10, part1(&input));
+        assert_eq!(5, part2(&input).unwrap());
+
+        let input = Grid::parse_digits("11111\n19991\n19191\n19991\n11111").unwrap();
+        assert_eq!(9, do_round(&mut input.map(Octopus::new)));
     }
 }
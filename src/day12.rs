@@ -1,3 +1,5 @@
+use crate::error::{Error, Result};
+use crate::solution::Solution;
 use itertools::Itertools;
 use std::collections::HashMap;
 use std::collections::HashSet;
@@ -47,21 +49,21 @@ struct Tracker {
 }
 
 impl Tracker {
-    pub fn new(_input: impl Iterator<Item = String>) -> Self {
+    pub fn new(_input: impl Iterator<Item = String>) -> Result<Self> {
         let mut tracker = Tracker {
             point_map: HashMap::new(),
         };
-        for line in _input {
+        for (i, line) in _input.enumerate() {
             // from-to
             let (from_s, to_s) = line
                 .trim()
                 .split('-')
                 .collect_tuple()
-                .expect("this is a tuple");
+                .ok_or_else(|| Error::parse(i + 1, 0, format!("expected from-to: {}", line)))?;
 
             tracker.track(from_s, to_s);
         }
-        tracker
+        Ok(tracker)
     }
     pub fn track(&mut self, from: &str, to: &str) {
         let (from_s, to_s) = (from.to_string(), to.to_string());
@@ -78,8 +80,10 @@ impl Tracker {
             .link(from_s);
     }
 
-    pub fn start(&self) -> &Point {
-        self.point_map.get("start").unwrap()
+    pub fn start(&self) -> Result<&Point> {
+        self.point_map
+            .get("start")
+            .ok_or_else(|| Error::invalid("there is no start cave"))
     }
 
     pub fn iter_next(&self, code: String) -> impl Iterator<Item = &Point> {
@@ -155,82 +159,129 @@ impl Tracker {
     }
 }
 
-pub fn part1(_input: impl Iterator<Item = CalculationInput>) -> u64 {
+pub fn part1(_input: impl Iterator<Item = CalculationInput>) -> Result<u64> {
     // when we process a point, then make it here
-    let tracker = Tracker::new(_input);
+    let tracker = Tracker::new(_input)?;
 
-    let start = tracker.start();
+    let start = tracker.start()?;
     let mut paths = 0;
     for path in tracker.traverse_part1(vec![], start) {
-        print!("{}", path[0]);
-        for p in path.iter().skip(1) {
-            print!(",{}", p);
-        }
-        println!();
+        crate::trace!("{}", path.join(","));
         paths += 1;
     }
-    paths
+    Ok(paths)
 }
 
-pub fn part2(_input: impl Iterator<Item = CalculationInput>) -> u64 {
-    let tracker = Tracker::new(_input);
+pub fn part2(_input: impl Iterator<Item = CalculationInput>) -> Result<u64> {
+    let tracker = Tracker::new(_input)?;
 
-    let start = tracker.start();
+    let start = tracker.start()?;
     let mut paths = 0;
-    for _path in tracker.traverse_part2(vec![], start) {
-        // print!("{}", _path[0]);
-        // for p in _path.iter().skip(1) {
-        //     print!(",{}", p);
-        // }
-        // println!();
+    for path in tracker.traverse_part2(vec![], start) {
+        crate::trace!("{}", path.join(","));
         paths += 1;
     }
-    paths
+    Ok(paths)
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u8 = 12;
+
+    type Input = Vec<CalculationInput>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(raw: &str) -> Result<Self::Input> {
+        Ok(raw.lines().map(String::from).collect())
+    }
+
+    fn validate(raw: &str) -> Vec<Error> {
+        let mut problems = super::util::check_lines(raw, |line| {
+            match line.trim().split('-').collect::<Vec<&str>>().as_slice() {
+                [from, to] if !from.is_empty() && !to.is_empty() => Ok(()),
+                _ => Err(Error::parse_line(0, format!("expected from-to: {}", line))),
+            }
+        });
+        for cave in ["start", "end"] {
+            let linked = raw
+                .lines()
+                .any(|line| line.trim().split('-').any(|c| c == cave));
+            if !linked {
+                problems.push(Error::parse(0, 0, format!("there is no {} cave", cave)));
+            }
+        }
+        problems
+    }
+
+    fn part1(input: Self::Input) -> Result<Self::Answer1> {
+        part1(input.into_iter())
+    }
+
+    fn part2(input: Self::Input) -> Result<Self::Answer2> {
+        part2(input.into_iter())
+    }
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::answers::verify;
+    use crate::solution::Part;
+    use crate::util::Kind;
 
     fn test_data(case: u8) -> impl Iterator<Item = CalculationInput> {
-        let source = format!("data/day{}_test_{}.txt", 12, case);
-        super::super::util::read_file(source.as_str())
+        super::super::util::DataDir::locate()
+            .read(12, Kind::Example(Some(case)))
+            .expect("test data")
     }
 
     fn puzzle_input() -> impl Iterator<Item = CalculationInput> {
-        super::super::util::read_data(12, false)
+        super::super::util::read_data(12, false).expect("puzzle input")
     }
 
     #[test]
     fn test_part1() {
-        let mut result = part1(test_data(1));
+        let mut result = part1(test_data(1)).unwrap();
         assert_eq!(10, result);
 
-        result = part1(test_data(2));
+        result = part1(test_data(2)).unwrap();
         assert_eq!(19, result);
 
-        result = part1(test_data(3));
+        result = part1(test_data(3)).unwrap();
         assert_eq!(226, result);
 
-        // 5756
-        let result = part1(puzzle_input());
-        println!("day 10 part 1: result={}", result);
+        let result = part1(puzzle_input()).unwrap();
+        verify(12, Part::One, "day12.txt", result);
     }
 
     #[test]
     fn test_part2() {
-        let mut result = part2(test_data(1));
+        let mut result = part2(test_data(1)).unwrap();
         assert_eq!(36, result);
 
-        result = part2(test_data(2));
+        result = part2(test_data(2)).unwrap();
         assert_eq!(103, result);
 
-        result = part2(test_data(3));
+        result = part2(test_data(3)).unwrap();
         assert_eq!(3509, result);
-        let result = part2(puzzle_input());
+        let result = part2(puzzle_input()).unwrap();
+        verify(12, Part::Two, "day12.txt", result);
+    }
 
-        // 144603
-        println!("day 10 part 2: result={}", result);
+    #[test]
+    fn test_bad_input() {
+        let lines = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<String>>();
+
+        assert_matches!(
+            part1(lines(&["start-A", "A-b-c"]).into_iter()),
+            Err(Error::Parse { line: 2, .. })
+        );
+        assert_matches!(
+            part2(lines(&["A-end"]).into_iter()),
+            Err(Error::InvalidState(_))
+        );
     }
 }
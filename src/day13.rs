@@ -1,3 +1,6 @@
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+use crate::util::Grid;
 use itertools::Itertools;
 use lazy_static::lazy_static;
 use regex::Regex;
@@ -5,8 +8,9 @@ use regex::Regex;
 type CalculationInput = String;
 type DayResult = usize;
 
-type Paper = Vec<Vec<bool>>;
+type Paper = Grid<bool>;
 
+#[derive(Debug)]
 pub struct Command {
     dir: char,
     value: usize,
@@ -18,17 +22,28 @@ impl Command {
         Command { dir, value }
     }
 
-    pub fn run(&self, paper: Paper) -> Paper {
+    pub fn run(&self, paper: Paper) -> Result<Paper> {
         // run this command on the paper, returning the result.
+        // the fold line must leave at least as much paper after it as before it
+        let size = match self.dir {
+            'x' => paper.width(),
+            _ => paper.height(),
+        };
+        if size <= self.value * 2 {
+            return Err(Error::invalid(format!(
+                "cannot fold along {}={} with size {}",
+                self.dir, self.value, size
+            )));
+        }
         match self.dir {
-            'x' => fold_vertical_line(self.value, paper),
-            'y' => fold_horizontal_line(self.value, paper),
+            'x' => Ok(fold_vertical_line(self.value, paper)),
+            'y' => Ok(fold_horizontal_line(self.value, paper)),
             _ => unreachable!(),
         }
     }
 }
 
-pub fn parse_command(input: String) -> Command {
+pub fn parse_command(input: String) -> Result<Command> {
     lazy_static! {
         static ref RE: Regex = Regex::new(r"^fold along (x|y)=([0-9]+)$").unwrap();
     }
@@ -36,29 +51,69 @@ pub fn parse_command(input: String) -> Command {
     // fold along x=5
     if let Some(cap) = RE.captures(input.as_str()) {
         assert_eq!(cap.len(), 3);
-        Command::new(
+        let value = cap.get(2).unwrap();
+        Ok(Command::new(
             cap.get(1).unwrap().as_str().chars().next().unwrap(),
-            cap.get(2).unwrap().as_str().parse::<usize>().unwrap(),
-        )
+            value
+                .as_str()
+                .parse::<usize>()
+                .map_err(|_| Error::parse_line(value.start() + 1, "number too large"))?,
+        ))
     } else {
-        unreachable!();
+        Err(Error::parse_line(
+            1,
+            format!("expected fold along x|y=N: {}", input),
+        ))
+    }
+}
+
+fn parse_dot(line: &str) -> Option<(usize, usize)> {
+    line.trim()
+        .split(',')
+        .map(|v| v.parse::<usize>().ok())
+        .collect_tuple()
+        .and_then(|(x, y)| Some((x?, y?)))
+}
+
+/// Every problem with the dots, the blank line after them, and the folds
+pub fn check(raw: &str) -> Vec<Error> {
+    let mut problems = Vec::new();
+    let mut lines = raw.lines().enumerate().map(|(i, line)| (i + 1, line));
+
+    let mut dots = 0;
+    for (line, dot) in lines.by_ref().take_while(|(_, v)| !v.trim().is_empty()) {
+        dots += 1;
+        if parse_dot(dot).is_none() {
+            problems.push(Error::parse(line, 0, format!("expected x,y: {}", dot)));
+        }
+    }
+    if dots == 0 {
+        problems.push(Error::parse(1, 0, "no dots"));
+    }
+
+    let mut folds = 0;
+    for (line, fold) in lines.filter(|(_, v)| !v.trim().is_empty()) {
+        folds += 1;
+        if let Err(e) = parse_command(fold.to_string()) {
+            problems.push(e.at_line(line));
+        }
     }
+    if folds == 0 {
+        problems.push(Error::parse(0, 0, "no fold instructions"));
+    }
+    problems
 }
 
-pub fn load_data(
-    mut _input: impl Iterator<Item = CalculationInput>,
-) -> (Vec<Vec<bool>>, Vec<Command>) {
+pub fn load_data(_input: impl Iterator<Item = CalculationInput>) -> Result<(Paper, Vec<Command>)> {
     let mut hits = Vec::new();
     let mut max_x: usize = 0;
     let mut max_y: usize = 0;
 
-    for hit in _input.by_ref().take_while(|v| !v.is_empty()) {
-        let tup: (usize, usize) = hit
-            .trim()
-            .split(',')
-            .map(|v| v.parse::<usize>().unwrap())
-            .collect_tuple()
-            .unwrap();
+    let mut lines = _input.enumerate().map(|(i, line)| (i + 1, line));
+
+    for (line, hit) in lines.by_ref().take_while(|(_, v)| !v.is_empty()) {
+        let tup: (usize, usize) = parse_dot(hit.as_str())
+            .ok_or_else(|| Error::parse(line, 0, format!("expected x,y: {}", hit)))?;
         hits.push(tup);
         if tup.0 > max_x {
             max_x = tup.0;
@@ -68,161 +123,199 @@ pub fn load_data(
         }
     }
 
-    let mut paper = vec![vec![false; max_x + 1]; max_y + 1];
-    for (x, y) in hits {
-        paper[y][x] = true;
+    let mut paper = Grid::new(max_x + 1, max_y + 1, false);
+    for hit in hits {
+        paper[hit] = true;
     }
 
-    let commands: Vec<Command> = _input.map(parse_command).collect();
-    (paper, commands)
+    let commands: Vec<Command> = lines
+        .map(|(line, v)| parse_command(v).map_err(|e| e.at_line(line)))
+        .collect::<Result<Vec<Command>>>()?;
+    Ok((paper, commands))
 }
 
-pub fn part1(_input: impl Iterator<Item = CalculationInput>) -> DayResult {
-    let (mut paper, commands): (Vec<Vec<bool>>, Vec<Command>) = load_data(_input);
+pub fn part1(_input: impl Iterator<Item = CalculationInput>) -> Result<DayResult> {
+    let (mut paper, commands): (Paper, Vec<Command>) = load_data(_input)?;
 
     for command in commands.iter().take(1) {
-        println!("fold -> {} -> {}", command.dir, command.value);
-        paper = command.run(paper);
-        // for line in paper.clone() {
-        //     for v in line {
-        //         if v {
-        //             print!("#");
-        //         } else {
-        //             print!(".");
-        //         }
-        //     }
-        //     println!();
-        // }
-    }
-
-    paper
-        .iter()
-        .map(|r: &Vec<bool>| r.iter().filter(|v| **v).count())
-        .sum()
+        crate::debug!("fold -> {} -> {}", command.dir, command.value);
+        paper = command.run(paper)?;
+        crate::trace!("after the fold:\n{}", render(&paper));
+    }
+
+    Ok(paper.values().filter(|v| **v).count())
 }
 
-pub fn part2(_input: impl Iterator<Item = CalculationInput>) -> DayResult {
-    let (mut paper, commands): (Vec<Vec<bool>>, Vec<Command>) = load_data(_input);
+pub fn part2(_input: impl Iterator<Item = CalculationInput>) -> Result<DayResult> {
+    let (mut paper, commands): (Paper, Vec<Command>) = load_data(_input)?;
 
     for command in commands {
-        println!("fold -> {} -> {}", command.dir, command.value);
-        paper = command.run(paper);
-    }
-
-    // this will print out the result
-    for line in paper.clone() {
-        for v in line {
-            if v {
-                print!("#");
-            } else {
-                print!(".");
-            }
-        }
-        println!();
+        crate::debug!("fold -> {} -> {}", command.dir, command.value);
+        paper = command.run(paper)?;
     }
 
-    paper
-        .iter()
-        .map(|r: &Vec<bool>| r.iter().filter(|v| **v).count())
-        .sum()
+    // the letters are the real answer
+    crate::info!("folded paper:\n{}", render(&paper));
+
+    Ok(paper.values().filter(|v| **v).count())
+}
+
+pub fn render(paper: &Paper) -> String {
+    paper.render(|v| if *v { '#' } else { '.' })
 }
 
 // in this case, we fold within a line
-pub fn fold_vertical_line(x: usize, paper: Vec<Vec<bool>>) -> Vec<Vec<bool>> {
-    paper
-        .iter()
-        .map(|row| {
-            assert!(row.len() > x);
-            (0..x).map(|ix| row[ix] || row[(x * 2) - ix]).collect()
-        })
-        .collect()
+pub fn fold_vertical_line(x: usize, paper: Paper) -> Paper {
+    assert!(paper.width() > x * 2);
+    Grid::from_fn(x, paper.height(), |col_ix, row_ix| {
+        paper[(col_ix, row_ix)] || paper[(x * 2 - col_ix, row_ix)]
+    })
 }
 
 // in this case, we fold rows up
-pub fn fold_horizontal_line(y: usize, paper: Vec<Vec<bool>>) -> Vec<Vec<bool>> {
-    paper
-        .iter()
-        .take(y)
-        .enumerate()
-        .map(|(row_ix, data)| {
-            // rix=0, y=3, o=6
-            let other_row = y * 2 - row_ix;
-            data.iter()
-                .enumerate()
-                .map(|(col_ix, value)| paper[other_row][col_ix] || *value)
-                .collect()
-        })
-        .collect()
+pub fn fold_horizontal_line(y: usize, paper: Paper) -> Paper {
+    assert!(paper.height() > y * 2);
+    Grid::from_fn(paper.width(), y, |col_ix, row_ix| {
+        // rix=0, y=3, o=6
+        let other_row = y * 2 - row_ix;
+        paper[(col_ix, row_ix)] || paper[(col_ix, other_row)]
+    })
+}
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u8 = 13;
+
+    type Input = Vec<CalculationInput>;
+    type Answer1 = DayResult;
+    type Answer2 = DayResult;
+
+    fn parse(raw: &str) -> Result<Self::Input> {
+        Ok(raw.lines().map(String::from).collect())
+    }
+
+    fn validate(raw: &str) -> Vec<Error> {
+        check(raw)
+    }
+
+    fn part1(input: Self::Input) -> Result<Self::Answer1> {
+        part1(input.into_iter())
+    }
+
+    fn part2(input: Self::Input) -> Result<Self::Answer2> {
+        part2(input.into_iter())
+    }
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::answers::verify;
+    use crate::solution::Part;
 
     fn test_data() -> impl Iterator<Item = CalculationInput> {
-        super::super::util::read_file("data/day13_test.txt")
+        super::super::util::read_file("data/day13_test.txt").expect("test data")
     }
 
     fn puzzle_input() -> impl Iterator<Item = CalculationInput> {
-        super::super::util::read_file("data/day13.txt")
+        super::super::util::read_file("data/day13.txt").expect("puzzle input")
+    }
+
+    fn paper(rows: &[&str]) -> Paper {
+        Grid::parse_chars(rows.join("\n").as_str(), |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })
+        .unwrap()
     }
 
     #[test]
     fn test_fold_vertical() {
-        let mut data: Vec<Vec<bool>> = vec![
-            vec![true, false, true, false, false, true, false],
-            vec![false, false, false, false, false, false, true],
-        ];
+        let mut data = paper(&["#.#..#.", "......#"]);
         data = fold_vertical_line(3, data);
-        assert_eq!(data.len(), 2);
-        assert_eq!(data[0].len(), 3);
-        assert!(data[0][0]);
-        assert!(data[0][1]);
-        assert!(data[0][2]);
-
-        assert_eq!(data[1].len(), 3);
-        assert!(data[1][0]);
-        assert!(!data[1][1]);
-        assert!(!data[1][2]);
+        assert_eq!(data.height(), 2);
+        assert_eq!(data.width(), 3);
+        assert_eq!(&[true, true, true], data.row(0));
+        assert_eq!(&[true, false, false], data.row(1));
     }
 
     #[test]
     fn test_fold_horizontal() {
-        let mut data: Vec<Vec<bool>> = vec![
-            vec![true, false, true, false, false, true, false],
-            vec![true, false, true, false, false, true, false],
-            vec![false, false, false, false, false, false, false],
-            vec![false, false, false, false, false, false, false],
-            vec![false, true, false, true, true, false, true],
-        ];
+        let mut data = paper(&["#.#..#.", "#.#..#.", ".......", ".......", ".#.##.#"]);
         data = fold_horizontal_line(2, data);
-        assert_eq!(data.len(), 2);
-        assert_eq!(data[0].len(), 7);
-        for i in 0..7 {
-            assert!(data[0][i]);
+        assert_eq!(data.height(), 2);
+        assert_eq!(data.width(), 7);
+        for value in data.row(0) {
+            assert!(value);
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let (mut data, commands) = load_data(test_data()).unwrap();
+        for command in commands {
+            data = command.run(data).unwrap();
         }
+        assert_eq!(
+            "#####\n#...#\n#...#\n#...#\n#####\n.....\n.....\n",
+            render(&data)
+        );
     }
 
     #[test]
     fn test_part1() {
-        let result = part1(test_data());
+        let result = part1(test_data()).unwrap();
 
         assert_eq!(17, result);
 
-        // ! not 95
-        let result = part1(puzzle_input());
-        println!("day 13 part 1: result={}", result);
+        let result = part1(puzzle_input()).unwrap();
+        verify(13, Part::One, "day13.txt", result);
     }
 
     #[test]
     fn test_part2() {
-        let result = part2(test_data());
+        let result = part2(test_data()).unwrap();
 
         assert_eq!(16, result);
 
-        let result = part2(puzzle_input());
+        let result = part2(puzzle_input()).unwrap();
 
-        println!("day 13 part 2: result={}", result);
+        verify(13, Part::Two, "day13.txt", result);
+    }
+
+    #[test]
+    fn test_bad_input() {
+        let lines = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<String>>();
+
+        assert_matches!(
+            load_data(lines(&["1,2", "3"]).into_iter()),
+            Err(Error::Parse { line: 2, .. })
+        );
+        assert_matches!(
+            load_data(lines(&["1,2", "", "fold along z=1"]).into_iter()),
+            Err(Error::Parse { line: 3, .. })
+        );
+        assert_matches!(
+            part1(lines(&["1,2", "4,4", "", "fold along y=3"]).into_iter()),
+            Err(Error::InvalidState(_))
+        );
+    }
+
+    #[test]
+    fn test_check() {
+        let raw = "1,2\n3\n4,x\n\nfold along y=1\nfold along z=2\n";
+        let problems: Vec<String> = check(raw).iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            vec![
+                "line 2: expected x,y: 3",
+                "line 3: expected x,y: 4,x",
+                "line 6, column 1: expected fold along x|y=N: fold along z=2",
+            ],
+            problems
+        );
+        assert_eq!(2, check("").len());
     }
 }
@@ -1,3 +1,5 @@
+use crate::error::{Error, Result};
+use crate::solution::Solution;
 use itertools::Itertools;
 use lazy_static::lazy_static;
 use regex::Regex;
@@ -6,12 +8,74 @@ use std::collections::HashMap;
 type CalculationInput = String;
 type DayResult = u128;
 
-pub fn run(mut _input: impl Iterator<Item = CalculationInput>, steps: u8) -> DayResult {
+/// One insertion rule, such as "CH -> B"
+pub fn parse_rule(line: &str) -> Result<((char, char), char)> {
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r"^([A-Z])([A-Z]) -> ([A-Z])$").unwrap();
+    }
+    let cap = RE.captures(line).ok_or_else(|| {
+        Error::parse_line(1, format!("expected insertion rule AB -> C: {}", line))
+    })?;
+    let element = |i: usize| cap.get(i).unwrap().as_str().chars().next().unwrap();
+    Ok(((element(1), element(2)), element(3)))
+}
+
+/// Every problem with the template, the blank line and the rules
+pub fn check(raw: &str) -> Vec<Error> {
+    let mut problems = Vec::new();
+    let mut lines = raw.lines().enumerate().map(|(i, line)| (i + 1, line));
+
+    match lines.next() {
+        None => return vec![Error::parse(1, 0, "no polymer template")],
+        Some((line, template)) => {
+            if template.len() < 2 {
+                problems.push(Error::parse(line, 0, "polymer template needs two elements"));
+            }
+            if let Some(i) = template.find(|c: char| !c.is_ascii_uppercase()) {
+                problems.push(Error::parse(line, i + 1, "elements must be A-Z"));
+            }
+        }
+    }
+    if let Some((line, blank)) = lines.next() {
+        if !blank.is_empty() {
+            problems.push(Error::parse(
+                line,
+                0,
+                "expected a blank line after the template",
+            ));
+        }
+    }
+
+    let mut seen: HashMap<(char, char), usize> = HashMap::new();
+    for (line, rule) in lines {
+        match parse_rule(rule) {
+            Ok((pair, _)) => {
+                if let Some(earlier) = seen.insert(pair, line) {
+                    problems.push(Error::parse(
+                        line,
+                        0,
+                        format!(
+                            "{}{} already has a rule on line {}",
+                            pair.0, pair.1, earlier
+                        ),
+                    ));
+                }
+            }
+            Err(e) => problems.push(e.at_line(line)),
+        }
+    }
+    problems
+}
+
+pub fn run(mut _input: impl Iterator<Item = CalculationInput>, steps: u8) -> Result<DayResult> {
     let start = _input
         .by_ref()
         .take_while(|v| !v.is_empty())
         .next()
-        .expect("present");
+        .ok_or_else(|| Error::parse(1, 0, "no polymer template"))?;
+    if start.len() < 2 {
+        return Err(Error::parse(1, 0, "polymer template needs two elements"));
+    }
 
     let mut counts: HashMap<(char, char), DayResult> =
         (0..start.len() - 1).fold(HashMap::new(), |mut acc, x| {
@@ -27,26 +91,10 @@ pub fn run(mut _input: impl Iterator<Item = CalculationInput>, steps: u8) -> Day
     _input.next();
 
     let mapping: HashMap<(char, char), char> = _input
-        .map(|line| {
-            lazy_static! {
-                static ref RE: Regex = Regex::new(r"^([A-Z][A-Z]) .. ([A-Z])$").unwrap();
-            }
-            if let Some(cap) = RE.captures(line.as_str()) {
-                (
-                    cap.get(1)
-                        .unwrap()
-                        .as_str()
-                        .chars()
-                        .collect_tuple()
-                        .unwrap(),
-                    cap.get(2).unwrap().as_str().chars().next().unwrap(),
-                )
-            } else {
-                println!("unreachable [{}]", line);
-                unreachable!();
-            }
-        })
-        .collect();
+        .enumerate()
+        // the template and the blank line come first
+        .map(|(i, line)| parse_rule(line.as_str()).map_err(|e| e.at_line(i + 3)))
+        .collect::<Result<HashMap<(char, char), char>>>()?;
 
     for _step in 0..steps {
         let mut counts_now = counts.clone();
@@ -60,11 +108,14 @@ pub fn run(mut _input: impl Iterator<Item = CalculationInput>, steps: u8) -> Day
         counts = counts_now;
     }
 
-    let mut result = HashMap::new();
-    for (k, v) in counts.iter() {
-        // println!("{},{} = {}", k.0, k.1, v);
-        *result.entry(k.0).or_insert(0 as f64) += (*v / 2) as f64;
-        *result.entry(k.1).or_insert(0 as f64) += (*v / 2) as f64;
+    // every element is the second of a pair, apart from the first one, so
+    // counting the seconds counts each element exactly once
+    let mut result: HashMap<char, DayResult> = HashMap::new();
+    result.insert(start.chars().next().unwrap(), 1);
+    for ((_, second), v) in counts.iter() {
+        if *v > 0 {
+            *result.entry(*second).or_insert(0) += *v;
+        }
     }
 
     let mut count_iter = result.iter();
@@ -74,7 +125,7 @@ pub fn run(mut _input: impl Iterator<Item = CalculationInput>, steps: u8) -> Day
     let mut max = *first.1;
 
     for (_char, v) in count_iter {
-        println!("{}={}", _char, v);
+        crate::debug!("{}={}", _char, v);
         if *v < min {
             min = *v;
         }
@@ -82,49 +133,105 @@ pub fn run(mut _input: impl Iterator<Item = CalculationInput>, steps: u8) -> Day
             max = *v;
         }
     }
-    println!("max={}, min={}", max, min);
-    (max.ceil() - min.ceil()) as u128
+    crate::debug!("max={}, min={}", max, min);
+    Ok(max - min)
 }
 
-pub fn part1(_input: impl Iterator<Item = CalculationInput>) -> DayResult {
+pub fn part1(_input: impl Iterator<Item = CalculationInput>) -> Result<DayResult> {
     run(_input, 10)
 }
 
-pub fn part2(_input: impl Iterator<Item = CalculationInput>) -> DayResult {
+pub fn part2(_input: impl Iterator<Item = CalculationInput>) -> Result<DayResult> {
     run(_input, 40)
 }
 
+pub struct Day14;
+
+impl Solution for Day14 {
+    const DAY: u8 = 14;
+
+    type Input = Vec<CalculationInput>;
+    type Answer1 = DayResult;
+    type Answer2 = DayResult;
+
+    fn parse(raw: &str) -> Result<Self::Input> {
+        Ok(raw.lines().map(String::from).collect())
+    }
+
+    fn validate(raw: &str) -> Vec<Error> {
+        check(raw)
+    }
+
+    fn part1(input: Self::Input) -> Result<Self::Answer1> {
+        part1(input.into_iter())
+    }
+
+    fn part2(input: Self::Input) -> Result<Self::Answer2> {
+        part2(input.into_iter())
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::answers::verify;
+    use crate::solution::Part;
 
     fn test_data() -> impl Iterator<Item = CalculationInput> {
-        super::super::util::read_file("data/day14_test.txt")
+        super::super::util::read_file("data/day14_test.txt").expect("test data")
     }
 
     fn puzzle_input() -> impl Iterator<Item = CalculationInput> {
-        super::super::util::read_file("data/day14.txt")
+        super::super::util::read_file("data/day14.txt").expect("puzzle input")
     }
 
     #[test]
     fn test_part1() {
-        let result = part1(test_data());
+        let result = part1(test_data()).unwrap();
 
         assert_eq!(1588, result);
 
-        // ! not 95
-        let result = part1(puzzle_input());
-        println!("day 14 part 1: result={}", result);
+        let result = part1(puzzle_input()).unwrap();
+        verify(14, Part::One, "day14.txt", result);
     }
 
     #[test]
     fn test_part2() {
-        let result = part2(test_data());
+        let result = part2(test_data()).unwrap();
         assert_eq!(2188189693529, result);
 
-        let result = part2(puzzle_input());
+        let result = part2(puzzle_input()).unwrap();
+
+        verify(14, Part::Two, "day14.txt", result);
+    }
 
-        println!("day 14 part 2: result={}", result);
+    #[test]
+    fn test_bad_input() {
+        let lines = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<String>>();
+
+        assert_matches!(
+            part1(lines(&[]).into_iter()),
+            Err(Error::Parse { line: 1, .. })
+        );
+        assert_matches!(
+            part1(lines(&["NNCB", "", "CH -> B", "HH -> n"]).into_iter()),
+            Err(Error::Parse { line: 4, .. })
+        );
+    }
+
+    #[test]
+    fn test_check() {
+        let raw = "NNcB\nx\nCH -> B\nHH -> N\nCH -> C\nCB .. H\n";
+        let problems: Vec<String> = check(raw).iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            vec![
+                "line 1, column 3: elements must be A-Z",
+                "line 2: expected a blank line after the template",
+                "line 5: CH already has a rule on line 3",
+                "line 6, column 1: expected insertion rule AB -> C: CB .. H",
+            ],
+            problems
+        );
     }
 }
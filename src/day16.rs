@@ -1,8 +1,9 @@
 use itertools::Itertools;
 type DayResult = u128;
-use std::str::Chars;
 
 use crate::day16::Packet::*;
+use crate::error::{Error, Result};
+use crate::solution::Solution;
 
 pub enum Packet {
     Literal {
@@ -39,312 +40,398 @@ impl Packet {
         }
     }
 
-    fn value(&self) -> DayResult {
+    fn value(&self) -> Result<DayResult> {
         match self {
             Literal {
                 version: _,
                 bin_value: _,
                 int_value,
-            } => *int_value as u128,
+            } => Ok(*int_value as u128),
             Operator {
                 version: _,
                 packet_type,
                 length_type_id: _,
                 packets,
             } => {
-                let iter = packets.iter().map(|p| p.value());
+                let values = packets
+                    .iter()
+                    .map(|p| p.value())
+                    .collect::<Result<Vec<DayResult>>>()?;
+                let iter = values.iter().copied();
+
+                let missing = || Error::invalid(format!("operator {} has no packets", packet_type));
+                let pair = || {
+                    iter.clone().collect_tuple().ok_or_else(|| {
+                        Error::invalid(format!(
+                            "operator {} needs two packets, found {}",
+                            packet_type,
+                            values.len()
+                        ))
+                    })
+                };
 
                 match *packet_type {
-                    0 => iter.sum(),
-                    1 => iter.product(),
-                    2 => iter.min().unwrap(),
-                    3 => iter.max().unwrap(),
+                    0 => total(iter),
+                    1 => product(iter),
+                    2 => iter.min().ok_or_else(missing),
+                    3 => iter.max().ok_or_else(missing),
                     5 => {
-                        let (a, b) = iter.collect_tuple().unwrap();
+                        let (a, b) = pair()?;
                         if a > b {
-                            1
+                            Ok(1)
                         } else {
-                            0
+                            Ok(0)
                         }
                     }
                     6 => {
-                        let (a, b) = iter.collect_tuple().unwrap();
+                        let (a, b) = pair()?;
                         if a < b {
-                            1
+                            Ok(1)
                         } else {
-                            0
+                            Ok(0)
                         }
                     }
                     7 => {
-                        let (a, b) = iter.collect_tuple().unwrap();
+                        let (a, b) = pair()?;
                         if a == b {
-                            1
+                            Ok(1)
                         } else {
-                            0
+                            Ok(0)
                         }
                     }
-                    _ => unreachable!(),
+                    _ => Err(Error::invalid(format!(
+                        "unknown packet type {}",
+                        packet_type
+                    ))),
                 }
             }
         }
     }
 
-    fn read_literal(version: u8, ci: &mut Chars) -> Option<Packet> {
-        // need to have at least one op
-        let mut five: String = ci.take(5).collect();
+    fn read_literal(version: u8, reader: &mut Reader) -> Result<Packet> {
+        let start = reader.offset;
         let mut bin_value = String::new();
-
-        while five.len() == 5 {
-            let first = five.chars().take(1).next().unwrap();
-            let segment: String = five.chars().skip(1).take(4).collect();
-            bin_value.push_str(segment.as_str());
-
-            if first == '0' {
-                // this is the last iteration
+        loop {
+            let group = reader.take(5, "literal group")?;
+            bin_value.push_str(&group[1..]);
+            if group.starts_with('0') {
+                // this is the last group
                 break;
             }
-            // there is at least one more iteration
-            assert_eq!(first, '1');
-            five = ci.take(5).collect();
         }
 
-        if !bin_value.is_empty() {
-            let int_value = u64::from_str_radix(bin_value.as_str(), 2).unwrap();
-            Some(Literal {
-                version,
-                bin_value,
-                int_value,
-            })
-        } else {
-            None
+        let digits = bin_value.trim_start_matches('0').len();
+        if digits > 64 {
+            return Err(error_at(
+                start,
+                format!("a literal of {} bits is wider than 64", digits),
+            ));
         }
+        let int_value = bin_value
+            .bytes()
+            .fold(0, |acc, b| acc << 1 | (b - b'0') as u64);
+        Ok(Literal {
+            version,
+            bin_value,
+            int_value,
+        })
     }
 
-    fn read_operator(version: u8, packet_type: u8, ci: &mut Chars) -> Option<Packet> {
-        if let Some(ltid) = ci.next() {
-            let length_type_id: u8 = match ltid {
-                '0' => 0,
-                '1' => 1,
-                _ => unreachable!(),
-            };
+    fn read_operator(version: u8, packet_type: u8, reader: &mut Reader) -> Result<Packet> {
+        let start = reader.offset;
+        let length_type_id = reader.number(1, "length type")? as u8;
 
-            if length_type_id == 0 {
-                let next_15: String = ci.take(15).collect();
-                if next_15.len() < 15 {
-                    return None;
-                }
-                let packet_bits = bin_to_usize(next_15);
-                let packet_data: String = ci.take(packet_bits as usize).collect();
-                let packets = run(packet_data, -1);
-
-                Some(Operator {
-                    version,
-                    packet_type,
-                    length_type_id,
-                    packets,
-                })
-            } else {
-                let next_11: String = ci.take(11).collect();
-                if next_11.len() < 11 {
-                    return None;
-                }
-                let max_packets = bin_to_usize(next_11);
-                let packets = run_chars(ci, max_packets as i32);
-                assert_eq!(max_packets as usize, packets.len());
-
-                Some(Operator {
-                    version,
-                    packet_type,
-                    length_type_id,
-                    packets,
-                })
-            }
+        let packets = if length_type_id == 0 {
+            let packet_bits = reader.number(15, "sub-packet length")?;
+            let mut sub_packets = Reader {
+                offset: reader.offset,
+                bits: reader.take(packet_bits, "sub-packets")?,
+            };
+            run_chars(&mut sub_packets, None)?
         } else {
-            None
+            let max_packets = reader.number(11, "sub-packet count")?;
+            let packets = run_chars(reader, Some(max_packets))?;
+            if packets.len() != max_packets {
+                return Err(error_at(
+                    start,
+                    format!(
+                        "operator needs {} sub-packets, found {}",
+                        max_packets,
+                        packets.len()
+                    ),
+                ));
+            }
+            packets
+        };
+
+        Ok(Operator {
+            version,
+            packet_type,
+            length_type_id,
+            packets,
+        })
+    }
+}
+
+/// The bits of a transmission still to be read, and how far into it they
+/// start
+struct Reader<'a> {
+    bits: &'a str,
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize, what: &str) -> Result<&'a str> {
+        if self.bits.len() < n {
+            return Err(error_at(
+                self.offset,
+                format!("{} needs {} bits, found {}", what, n, self.bits.len()),
+            ));
         }
+        let (taken, rest) = self.bits.split_at(n);
+        self.bits = rest;
+        self.offset += n;
+        Ok(taken)
+    }
+
+    fn number(&mut self, n: usize, what: &str) -> Result<usize> {
+        self.take(n, what).map(bin_to_usize)
+    }
+
+    /// nothing left but the zeros filling out the last hex digit
+    fn is_padding(&self) -> bool {
+        self.bits.bytes().all(|b| b == b'0')
     }
 }
 
-fn hex_to_bin(input: String) -> String {
-    input
-        .trim()
-        .chars()
-        .fold(String::new(), |mut acc, c| match c {
-            '0' => {
-                acc.push_str("0000");
-                acc
-            }
-            '1' => {
-                acc.push_str("0001");
-                acc
-            }
-            '2' => {
-                acc.push_str("0010");
-                acc
-            }
-            '3' => {
-                acc.push_str("0011");
-                acc
-            }
-            '4' => {
-                acc.push_str("0100");
-                acc
-            }
-            '5' => {
-                acc.push_str("0101");
-                acc
-            }
-            '6' => {
-                acc.push_str("0110");
-                acc
-            }
-            '7' => {
-                acc.push_str("0111");
-                acc
-            }
-            '8' => {
-                acc.push_str("1000");
-                acc
-            }
-            '9' => {
-                acc.push_str("1001");
-                acc
-            }
-            'A' => {
-                acc.push_str("1010");
-                acc
-            }
-            'B' => {
-                acc.push_str("1011");
-                acc
-            }
-            'C' => {
-                acc.push_str("1100");
-                acc
-            }
-            'D' => {
-                acc.push_str("1101");
-                acc
-            }
-            'E' => {
-                acc.push_str("1110");
-                acc
-            }
-            'F' => {
-                acc.push_str("1111");
-                acc
-            }
-            _ => unreachable!(),
-        })
+/// a problem with the bit at `offset`, in the column of its hex digit
+fn error_at(offset: usize, message: impl Into<String>) -> Error {
+    Error::parse_line(
+        offset / 4 + 1,
+        format!("bit {}: {}", offset, message.into()),
+    )
 }
 
-fn bin_to_int(input: String) -> u8 {
-    u8::from_str_radix(input.as_str(), 2).expect("to parse")
+fn overflow() -> Error {
+    Error::invalid("packet value overflows")
 }
 
-fn bin_to_usize(input: String) -> usize {
-    usize::from_str_radix(input.as_str(), 2).expect("to parse")
+/// the sum of some packet values, if it fits
+fn total(mut values: impl Iterator<Item = DayResult>) -> Result<DayResult> {
+    values.try_fold(0, |acc: DayResult, v| {
+        acc.checked_add(v).ok_or_else(overflow)
+    })
 }
 
-fn run(data: String, max_packets: i32) -> Vec<Packet> {
-    let mut chars = data.chars();
-    let char_iter = chars.by_ref();
-    run_chars(char_iter, max_packets)
+/// the product of some packet values, if it fits
+fn product(mut values: impl Iterator<Item = DayResult>) -> Result<DayResult> {
+    values.try_fold(1, |acc: DayResult, v| {
+        acc.checked_mul(v).ok_or_else(overflow)
+    })
 }
 
-fn try_header(data_iter: &mut Chars) -> Option<(u8, u8)> {
-    let v_str: String = data_iter.take(3).collect();
-    if v_str.len() < 3 {
-        return None;
-    }
-    let t_str: String = data_iter.take(3).collect();
-    if t_str.len() < 3 {
-        return None;
-    }
-    Some((bin_to_int(v_str), bin_to_int(t_str)))
+/// either case of hex digit is fine
+fn hex_to_bin(input: String) -> Result<String> {
+    input
+        .trim()
+        .chars()
+        .enumerate()
+        .try_fold(String::new(), |mut acc, (i, c)| {
+            let digit = c
+                .to_digit(16)
+                .ok_or_else(|| Error::parse_line(i + 1, format!("not a hex digit: {}", c)))?;
+            acc.push_str(format!("{:04b}", digit).as_str());
+            Ok(acc)
+        })
 }
 
-fn run_chars(data_iter: &mut Chars, mut max_packets: i32) -> Vec<Packet> {
-    let mut packets = Vec::new();
-    // let data_iter = data_iter.by_ref();
+fn bin_to_usize(input: &str) -> usize {
+    input
+        .bytes()
+        .fold(0, |acc, b| acc << 1 | (b - b'0') as usize)
+}
 
-    let mut maybe_header = try_header(data_iter);
+fn run(data: &str) -> Result<Vec<Packet>> {
+    run_chars(
+        &mut Reader {
+            bits: data,
+            offset: 0,
+        },
+        None,
+    )
+}
 
-    while let Some((packet_version, packet_type)) = maybe_header {
-        if packet_type == 4 {
-            if let Some(literal) = Packet::read_literal(packet_version, data_iter) {
-                packets.push(literal);
-            } else {
-                // we have run out of data
-                break;
-            }
+/// Read packets until there are `max_packets` of them, or only padding is
+/// left
+fn run_chars(reader: &mut Reader, max_packets: Option<usize>) -> Result<Vec<Packet>> {
+    let mut packets = Vec::new();
+
+    while max_packets != Some(packets.len()) && !reader.is_padding() {
+        let packet_version = reader.number(3, "version")? as u8;
+        let packet_type = reader.number(3, "packet type")? as u8;
+        let packet = if packet_type == 4 {
+            Packet::read_literal(packet_version, reader)?
         } else {
             // we have an operator
-            if let Some(operator) = Packet::read_operator(packet_version, packet_type, data_iter) {
-                packets.push(operator);
-            } else {
-                // we have run out of data
-                break;
-            }
-        }
-
-        if max_packets > 0 {
-            max_packets -= 1;
-            if max_packets == 0 {
-                break;
-            }
-        }
-        maybe_header = try_header(data_iter);
+            Packet::read_operator(packet_version, packet_type, reader)?
+        };
+        packets.push(packet);
     }
 
-    packets
+    Ok(packets)
 }
 
-pub fn part1(_input: String) -> DayResult {
-    run(hex_to_bin(_input), -1)
+pub fn part1(_input: String) -> Result<DayResult> {
+    Ok(run(&hex_to_bin(_input)?)?
         .iter()
         .map(|p| p.version_count())
-        .sum()
+        .sum())
 }
 
-pub fn part2(_input: String) -> DayResult {
-    run(hex_to_bin(_input), -1).iter().map(|p| p.value()).sum()
+pub fn part2(_input: String) -> Result<DayResult> {
+    let values = run(&hex_to_bin(_input)?)?
+        .iter()
+        .map(|p| p.value())
+        .collect::<Result<Vec<DayResult>>>()?;
+    total(values.into_iter())
+}
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    const DAY: u8 = 16;
+
+    type Input = String;
+    type Answer1 = DayResult;
+    type Answer2 = DayResult;
+
+    fn parse(raw: &str) -> Result<Self::Input> {
+        raw.lines()
+            .map(|line| line.trim().to_string())
+            .find(|line| !line.is_empty())
+            .ok_or_else(|| Error::parse(1, 0, "there should be one line"))
+    }
+
+    fn validate(raw: &str) -> Vec<Error> {
+        super::util::check_single_line(raw, |line| hex_to_bin(line.trim().to_string()))
+    }
+
+    fn part1(input: Self::Input) -> Result<Self::Answer1> {
+        part1(input)
+    }
+
+    fn part2(input: Self::Input) -> Result<Self::Answer2> {
+        part2(input)
+    }
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::answers::verify;
+    use crate::solution::Part;
 
     fn puzzle_input() -> String {
         super::super::util::read_file("data/day16.txt")
+            .expect("puzzle input")
             .next()
             .expect("one line")
     }
 
     #[test]
     fn test_part1() {
-        assert_eq!(16, part1("8A004A801A8002F478".to_string()));
-        assert_eq!(12, part1("620080001611562C8802118E34".to_string()));
-        assert_eq!(23, part1("C0015000016115A2E0802F182340".to_string()));
-        assert_eq!(31, part1("A0016C880162017C3686B18A3D4780".to_string()));
-
-        let result = part1(puzzle_input());
-        println!("day 16 part 1: result={}", result);
+        assert_eq!(16, part1("8A004A801A8002F478".to_string()).unwrap());
+        assert_eq!(12, part1("620080001611562C8802118E34".to_string()).unwrap());
+        assert_eq!(
+            23,
+            part1("C0015000016115A2E0802F182340".to_string()).unwrap()
+        );
+        assert_eq!(
+            31,
+            part1("A0016C880162017C3686B18A3D4780".to_string()).unwrap()
+        );
+
+        let result = part1(puzzle_input()).unwrap();
+        verify(16, Part::One, "day16.txt", result);
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(3, part2("C200B40A82".to_string()));
-        assert_eq!(54, part2("04005AC33890".to_string()));
-        assert_eq!(7, part2("880086C3E88112".to_string()));
-        assert_eq!(9, part2("CE00C43D881120".to_string()));
-        assert_eq!(1, part2("D8005AC2A8F0".to_string()));
-        assert_eq!(0, part2("F600BC2D8F".to_string()));
-        assert_eq!(0, part2("9C005AC2F8F0".to_string()));
-        assert_eq!(1, part2("9C0141080250320F1802104A08".to_string()));
-
-        let result = part2(puzzle_input());
-        println!("day 16 part 2: result={}", result);
+        assert_eq!(3, part2("C200B40A82".to_string()).unwrap());
+        assert_eq!(54, part2("04005AC33890".to_string()).unwrap());
+        assert_eq!(7, part2("880086C3E88112".to_string()).unwrap());
+        assert_eq!(9, part2("CE00C43D881120".to_string()).unwrap());
+        assert_eq!(1, part2("D8005AC2A8F0".to_string()).unwrap());
+        assert_eq!(0, part2("F600BC2D8F".to_string()).unwrap());
+        assert_eq!(0, part2("9C005AC2F8F0".to_string()).unwrap());
+        assert_eq!(1, part2("9C0141080250320F1802104A08".to_string()).unwrap());
+
+        let result = part2(puzzle_input()).unwrap();
+        verify(16, Part::Two, "day16.txt", result);
+    }
+
+    #[test]
+    fn test_bad_input() {
+        assert_matches!(
+            hex_to_bin("8A0G".to_string()),
+            Err(Error::Parse { column: 4, .. })
+        );
+        assert_eq!(16, part1("8a004a801a8002f478".to_string()).unwrap());
+        // a less-than operator (type 6) with a single literal sub-packet
+        assert_matches!(part2("DA004428".to_string()), Err(Error::InvalidState(_)));
+
+        // the example with three sub-packets, missing the end of the last one
+        let e = part1("EE00D40C8230".to_string()).unwrap_err();
+        assert_eq!(
+            "column 12: bit 46: literal group needs 5 bits, found 2",
+            e.to_string()
+        );
+        // a count of two with only one sub-packet and then padding
+        let short = "000000100000000010000100000010000";
+        assert_eq!(
+            Some("column 2: bit 6: operator needs 2 sub-packets, found 1".to_string()),
+            run(short).err().map(|e| e.to_string())
+        );
+        // a length that runs past the end
+        assert_matches!(
+            part1("38006F45291200".to_string()[..8].to_string()),
+            Err(Error::Parse { column: 6, .. })
+        );
+        // 68 bits of ones
+        let wide = format!("000100{}01111", "11111".repeat(16));
+        assert_eq!(
+            Some("column 2: bit 6: a literal of 68 bits is wider than 64".to_string()),
+            run(&wide).err().map(|e| e.to_string())
+        );
+        // but leading zeros don't count
+        let padded = format!("000100{}01111", "10000".repeat(16));
+        assert_eq!(15, run(&padded).unwrap()[0].value().unwrap());
+    }
+
+    #[test]
+    fn test_overflow() {
+        let hex = |bits: String| {
+            let bits = format!("{:0<1$}", bits, bits.len().div_ceil(4) * 4);
+            bits.as_bytes()
+                .chunks(4)
+                .map(|c| format!("{:X}", bin_to_usize(std::str::from_utf8(c).unwrap())))
+                .collect::<String>()
+        };
+        // 64 bits of ones, and a product (type 1) of `n` of them
+        let ones = format!("000100{}01111", "11111".repeat(15));
+        let product = |n: usize| format!("0000011{:011b}{}", n, ones.repeat(n));
+
+        let square = (u64::MAX as DayResult).pow(2);
+        assert_eq!(square, part2(hex(product(2))).unwrap());
+        assert_matches!(part2(hex(product(3))), Err(Error::InvalidState(_)));
+        // a sum (type 0) of two squares
+        let sum = format!("0000001{:011b}{}", 2, product(2).repeat(2));
+        assert_matches!(part2(hex(sum)), Err(Error::InvalidState(_)));
+        // and two squares side by side
+        assert_matches!(
+            part2(hex(product(2).repeat(2))),
+            Err(Error::InvalidState(_))
+        );
     }
 }
@@ -1,3 +1,6 @@
+use crate::error::{Error, Result};
+use crate::report::Report;
+use crate::solution::Solution;
 use lazy_static::lazy_static;
 use regex::Regex;
 
@@ -44,7 +47,7 @@ impl TargetArea {
     }
 }
 
-pub fn parse(_input: &str) -> TargetArea {
+pub fn parse(_input: &str) -> Result<TargetArea> {
     lazy_static! {
         static ref RE: Regex =
             Regex::new(r"^target area: x=(-?[0-9]+)\.\.(-?[0-9]+), y=(-?[0-9]+)\.\.(-?[0-9]+)$")
@@ -54,25 +57,28 @@ pub fn parse(_input: &str) -> TargetArea {
     // fold along x=5
     if let Some(cap) = RE.captures(_input) {
         assert_eq!(cap.len(), 5);
-        TargetArea {
-            x: (
-                cap.get(1).unwrap().as_str().parse::<i32>().unwrap(),
-                cap.get(2).unwrap().as_str().parse::<i32>().unwrap(),
-            ),
-            y: (
-                cap.get(3).unwrap().as_str().parse::<i32>().unwrap(),
-                cap.get(4).unwrap().as_str().parse::<i32>().unwrap(),
-            ),
-        }
+        let number = |i: usize| {
+            let m = cap.get(i).unwrap();
+            m.as_str()
+                .parse::<i32>()
+                .map_err(|_| Error::parse_line(m.start() + 1, "number too large"))
+        };
+        Ok(TargetArea {
+            x: (number(1)?, number(2)?),
+            y: (number(3)?, number(4)?),
+        })
     } else {
-        unreachable!()
+        Err(Error::parse_line(
+            1,
+            format!("expected target area: x=A..B, y=C..D: {}", _input),
+        ))
     }
 }
 
 fn check(target: &TargetArea, velocity: &(i32, i32)) -> (TargetState, i32) {
     let (mut x_vel, mut y_vel) = *velocity;
 
-    println!("checking velocity x={}, y={}", x_vel, y_vel);
+    crate::trace!("checking velocity x={}, y={}", x_vel, y_vel);
 
     let mut position = (0, 0);
     // this will always be before
@@ -108,7 +114,7 @@ fn check(target: &TargetArea, velocity: &(i32, i32)) -> (TargetState, i32) {
 
 pub fn run(target: TargetArea) -> Vec<((i32, i32), i32)> {
     // what is the highest y position on the trajectory
-    println!("{:?}", target);
+    crate::debug!("{:?}", target);
 
     let mut success = Vec::new();
 
@@ -139,34 +145,89 @@ pub fn run(target: TargetArea) -> Vec<((i32, i32), i32)> {
     success
 }
 
-pub fn part1(target: TargetArea) -> i32 {
-    *run(target).iter().map(|(_, max_y)| max_y).max().unwrap()
+pub fn part1(target: TargetArea) -> Result<i32> {
+    run(target)
+        .iter()
+        .map(|(_, max_y)| *max_y)
+        .max()
+        .ok_or_else(|| Error::invalid("no velocity reaches the target area"))
 }
 
 pub fn part2(target: TargetArea) -> usize {
     run(target).len()
 }
 
+pub struct Day17;
+
+impl Solution for Day17 {
+    const DAY: u8 = 17;
+
+    type Input = TargetArea;
+    type Answer1 = i32;
+    type Answer2 = usize;
+
+    fn parse(raw: &str) -> Result<Self::Input> {
+        raw.lines()
+            .enumerate()
+            .find(|(_, line)| !line.trim().is_empty())
+            .ok_or_else(|| Error::parse(1, 0, "there should be one line"))
+            .and_then(|(i, line)| parse(line.trim()).map_err(|e| e.at_line(i + 1)))
+    }
+
+    fn validate(raw: &str) -> Vec<Error> {
+        super::util::check_single_line(raw, |line| parse(line.trim()))
+    }
+
+    fn part1(input: Self::Input) -> Result<Self::Answer1> {
+        part1(input)
+    }
+
+    fn part2(input: Self::Input) -> Result<Self::Answer2> {
+        Ok(part2(input))
+    }
+
+    fn report1(input: Self::Input) -> Result<Report> {
+        let success = run(input);
+        let (velocity, max_y) = success
+            .iter()
+            .max_by_key(|(_, max_y)| *max_y)
+            .ok_or_else(|| Error::invalid("no velocity reaches the target area"))?;
+        Ok(Report::new(max_y)
+            .with("max_y", *max_y)
+            .with("velocity", *velocity))
+    }
+
+    fn report2(input: Self::Input) -> Result<Report> {
+        let success = run(input);
+        let velocities: Vec<(i32, i32)> = success.iter().map(|(v, _)| *v).collect();
+        Ok(Report::new(velocities.len())
+            .with("count", velocities.len())
+            .with("velocities", velocities))
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::answers::verify;
+    use crate::solution::Part;
 
     fn test_input() -> TargetArea {
-        parse("target area: x=20..30, y=-10..-5")
+        parse("target area: x=20..30, y=-10..-5").unwrap()
     }
 
     fn puzzle_input() -> TargetArea {
-        parse("target area: x=211..232, y=-124..-69")
+        parse("target area: x=211..232, y=-124..-69").unwrap()
     }
 
     #[test]
     fn test_part1() {
-        let result = part1(test_input());
+        let result = part1(test_input()).unwrap();
         assert_eq!(45, result);
 
-        let result = part1(puzzle_input());
-        println!("day 17 part 1: result={}", result);
+        let result = part1(puzzle_input()).unwrap();
+        verify(17, Part::One, "day17.txt", result);
     }
 
     #[test]
@@ -175,6 +236,19 @@ mod tests {
         assert_eq!(112, result);
 
         let result = part2(puzzle_input());
-        println!("day 17 part 2: result={}", result);
+        verify(17, Part::Two, "day17.txt", result);
+    }
+
+    #[test]
+    fn test_bad_input() {
+        assert_matches!(parse("target area: x=20..30"), Err(Error::Parse { .. }));
+        assert_matches!(
+            Day17::parse("\ntarget area: x=a..b, y=c..d"),
+            Err(Error::Parse { line: 2, .. })
+        );
+
+        // behind the launcher, so it can never be reached
+        let target = parse("target area: x=-30..-20, y=-10..-5").unwrap();
+        assert_matches!(part1(target), Err(Error::InvalidState(_)));
     }
 }
@@ -1,93 +1,451 @@
+pub mod script;
+pub mod trajectory;
+
+use crate::error::{Error, Result};
+use crate::report::Report;
+use crate::solution::Solution;
+use crate::stream::Streaming;
+use std::fmt;
+
+/// One line of a course
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Command {
+    Forward(i32),
+    Up(i32),
+    Down(i32),
+    /// astern, the opposite of forward
+    Back(i32),
+}
+
+impl Command {
+    /// Parse a line such as "forward 5"
+    /// ```
+    /// use aoc2021::day2::Command;
+    ///
+    /// assert_eq!(Command::Down(3), Command::parse("down 3").unwrap());
+    /// let e = Command::parse("sideways 3").unwrap_err();
+    /// assert_eq!("column 1: unknown direction: sideways", e.to_string());
+    /// ```
+    pub fn parse(line: &str) -> Result<Self> {
+        let (direction, value) = super::util::parse_string_int(line.to_string())?;
+        match direction.as_str() {
+            "forward" => Ok(Command::Forward(value)),
+            "up" => Ok(Command::Up(value)),
+            "down" => Ok(Command::Down(value)),
+            "back" => Ok(Command::Back(value)),
+            _ => Err(Error::parse_line(
+                line.find(direction.as_str()).unwrap_or(0) + 1,
+                format!("unknown direction: {}", direction),
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Command::Forward(n) => write!(f, "forward {}", n),
+            Command::Up(n) => write!(f, "up {}", n),
+            Command::Down(n) => write!(f, "down {}", n),
+            Command::Back(n) => write!(f, "back {}", n),
+        }
+    }
+}
+
+/// Where the submarine is and which way it is pointing
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Submarine {
+    pub depth: i64,
+    pub horiz: i64,
+    pub aim: i64,
+}
+
+impl Submarine {
+    /// depth times horizontal position, the puzzle's answer
+    pub fn distance(&self) -> i128 {
+        self.depth as i128 * self.horiz as i128
+    }
+}
+
+/// `value` moved by `by`, unless the submarine can't count that far
+fn moved(value: i64, by: Option<i64>, command: Command) -> Result<i64> {
+    by.and_then(|by| value.checked_add(by)).ok_or_else(|| {
+        Error::invalid(format!(
+            "{} goes further than the submarine can count",
+            command
+        ))
+    })
+}
+
+/// A way of interpreting the commands, which fails when the submarine
+/// goes further than it can count. Any `Fn(&mut Submarine, Command) ->
+/// Result<()>` is one too.
+pub trait Model {
+    fn apply(&self, submarine: &mut Submarine, command: Command) -> Result<()>;
+}
+
+impl<F: Fn(&mut Submarine, Command) -> Result<()>> Model for F {
+    fn apply(&self, submarine: &mut Submarine, command: Command) -> Result<()> {
+        self(submarine, command)
+    }
+}
+
+/// Part 1: up and down change the depth
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Direct;
+
+impl Model for Direct {
+    fn apply(&self, submarine: &mut Submarine, command: Command) -> Result<()> {
+        let (value, by) = match command {
+            Command::Forward(n) => (&mut submarine.horiz, i64::from(n)),
+            Command::Back(n) => (&mut submarine.horiz, -i64::from(n)),
+            Command::Up(n) => (&mut submarine.depth, -i64::from(n)),
+            Command::Down(n) => (&mut submarine.depth, i64::from(n)),
+        };
+        *value = moved(*value, Some(by), command)?;
+        Ok(())
+    }
+}
+
+/// Part 2: up and down change the aim, and going forward dives along it (and
+/// going back climbs along it)
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Aim;
+
+impl Model for Aim {
+    fn apply(&self, submarine: &mut Submarine, command: Command) -> Result<()> {
+        let along = |n: i64| {
+            let horiz = moved(submarine.horiz, Some(n), command)?;
+            let depth = moved(submarine.depth, submarine.aim.checked_mul(n), command)?;
+            Ok((horiz, depth))
+        };
+        match command {
+            Command::Forward(n) => (submarine.horiz, submarine.depth) = along(n.into())?,
+            Command::Back(n) => (submarine.horiz, submarine.depth) = along(-i64::from(n))?,
+            Command::Up(n) => submarine.aim = moved(submarine.aim, Some(-i64::from(n)), command)?,
+            Command::Down(n) => submarine.aim = moved(submarine.aim, Some(n.into()), command)?,
+        }
+        Ok(())
+    }
+}
+
+/// Follow a course from the surface
+pub fn navigate(
+    model: &(impl Model + ?Sized),
+    commands: impl IntoIterator<Item = Command>,
+) -> Result<Submarine> {
+    let mut submarine = Submarine::default();
+    for command in commands {
+        model.apply(&mut submarine, command)?;
+    }
+    Ok(submarine)
+}
+
+/// Follow the same course under each model, to see where each ends up
+/// ```
+/// use aoc2021::day2::{self, Aim, Command, Direct, Model, Submarine};
+///
+/// let course = [Command::Down(2), Command::Forward(3)];
+/// // forward goes twice as far
+/// let turbo = |s: &mut Submarine, c: Command| match c {
+///     Command::Forward(n) => Direct.apply(s, Command::Forward(2 * n)),
+///     c => Direct.apply(s, c),
+/// };
+/// let ends = day2::compare(&[&Direct, &Aim, &turbo], &course).unwrap();
+/// assert_eq!(vec![6, 18, 12], ends.iter().map(|s| s.distance()).collect::<Vec<_>>());
+/// ```
+pub fn compare(models: &[&dyn Model], commands: &[Command]) -> Result<Vec<Submarine>> {
+    models
+        .iter()
+        .map(|model| navigate(*model, commands.iter().copied()))
+        .collect()
+}
+
 /// This is my first rust doctest
 /// ```
-/// use aoc2021::day2::part1;
+/// use aoc2021::day2::{part1, Command};
 ///
-/// let case = vec![("down", 3), ("forward", 6), ("up", 1)];
+/// let case = "down 3\nforward 6\nup 1";
 ///
-/// let iter = case.iter().map(|(s, i)| (s.to_string(), *i));
+/// let commands = case.lines().map(|line| Command::parse(line).unwrap());
 ///
-/// let (depth, horiz, distance) = part1(iter);
+/// let (depth, horiz, distance) = part1(commands).unwrap();
 ///
 /// assert_eq!(2, depth);
 /// assert_eq!(6, horiz);
 /// assert_eq!(12, distance);
 /// ```
-pub fn part1(instructions: impl Iterator<Item = (String, i32)>) -> (i32, i32, i32) {
-    let mut depth: i32 = 0;
-    let mut horiz: i32 = 0;
+pub fn part1(commands: impl Iterator<Item = Command>) -> Result<(i64, i64, i128)> {
+    let submarine = navigate(&Direct, commands)?;
+    Ok((submarine.depth, submarine.horiz, submarine.distance()))
+}
 
-    for (direction, value) in instructions {
-        match direction.as_str() {
-            "up" => depth -= value,
-            "down" => depth += value,
-            "forward" => horiz += value,
-            _ => unreachable!(),
+pub fn part2(commands: impl Iterator<Item = Command>) -> Result<(i64, i64, i128, i64)> {
+    let submarine = navigate(&Aim, commands)?;
+    Ok((
+        submarine.depth,
+        submarine.horiz,
+        submarine.distance(),
+        submarine.aim,
+    ))
+}
+
+/// Follows a course a line at a time. Only plain courses can be streamed:
+/// a script's `repeat` and `macro` blocks span lines, so its lines are
+/// rejected like any other bad line, and a script has to be parsed whole
+/// with [`script::Script::parse`] instead.
+pub struct Navigator<M = Direct> {
+    model: M,
+    pub submarine: Submarine,
+}
+
+impl Navigator {
+    /// part 1's rules
+    pub fn new() -> Self {
+        Navigator::with_model(Direct)
+    }
+}
+
+impl Navigator<Aim> {
+    /// part 2's rules
+    pub fn with_aim() -> Self {
+        Navigator::with_model(Aim)
+    }
+}
+
+impl<M: Model> Navigator<M> {
+    pub fn with_model(model: M) -> Self {
+        Navigator {
+            model,
+            submarine: Submarine::default(),
         }
     }
-    (depth, horiz, depth * horiz)
+
+    pub fn apply(&mut self, command: Command) -> Result<()> {
+        self.model.apply(&mut self.submarine, command)
+    }
 }
 
-pub fn part2(instructions: impl Iterator<Item = (String, i32)>) -> (i32, i32, i32, i32) {
-    let mut depth: i32 = 0;
-    let mut horiz: i32 = 0;
-    let mut aim: i32 = 0;
+impl Default for Navigator {
+    fn default() -> Self {
+        Navigator::new()
+    }
+}
 
-    for (direction, value) in instructions {
-        match direction.as_str() {
-            "up" => aim -= value,
-            "down" => aim += value,
-            "forward" => {
-                horiz += value;
-                depth += aim * value;
-            }
-            _ => unreachable!(),
+impl<M: Model> Streaming for Navigator<M> {
+    type Answer = i128;
+
+    fn push(&mut self, line: &str) -> Result<()> {
+        self.apply(Command::parse(line)?)
+    }
+
+    /// depth times horizontal position
+    fn answer(&self) -> i128 {
+        self.submarine.distance()
+    }
+}
+
+/// Parse a line such as "forward 5", checking the direction
+pub fn parse_line(line: String) -> Result<Command> {
+    Command::parse(line.as_str())
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u8 = 2;
+
+    type Input = Vec<Command>;
+    type Answer1 = i128;
+    type Answer2 = i128;
+
+    /// a course is read a line at a time, unless it is written as a script
+    fn parse(raw: &str) -> Result<Self::Input> {
+        if !script::is_script(raw) {
+            return super::util::parse_lines(raw, parse_line);
         }
+        Ok(script::Script::parse(raw)?.expand())
+    }
+
+    /// every bad line of a plain course, but only the first problem in a script
+    fn validate(raw: &str) -> Vec<Error> {
+        if !script::is_script(raw) {
+            return super::util::check_lines(raw, parse_line);
+        }
+        match script::Script::parse(raw) {
+            Err(e) => vec![e],
+            Ok(script) if script.body.is_empty() => vec![super::util::empty_input()],
+            Ok(_) => Vec::new(),
+        }
+    }
+
+    fn part1(input: Self::Input) -> Result<Self::Answer1> {
+        Ok(part1(input.into_iter())?.2)
+    }
+
+    fn part2(input: Self::Input) -> Result<Self::Answer2> {
+        Ok(part2(input.into_iter())?.2)
+    }
+
+    fn report1(input: Self::Input) -> Result<Report> {
+        let (depth, horiz, distance) = part1(input.into_iter())?;
+        Ok(Report::new(distance)
+            .with("depth", depth)
+            .with("horiz", horiz)
+            .with("distance", distance))
+    }
+
+    fn report2(input: Self::Input) -> Result<Report> {
+        let (depth, horiz, distance, aim) = part2(input.into_iter())?;
+        Ok(Report::new(distance)
+            .with("depth", depth)
+            .with("horiz", horiz)
+            .with("distance", distance)
+            .with("aim", aim))
     }
-    (depth, horiz, depth * horiz, aim)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::answers::verify;
+    use crate::solution::Part;
 
-    fn test_data() -> impl Iterator<Item = (String, i32)> {
-        super::super::util::read_string_int_from_file("data/day2_test.txt")
+    fn test_data() -> impl Iterator<Item = Command> {
+        super::super::util::parse_file("data/day2_test.txt", parse_line).expect("test data")
     }
 
-    fn puzzle_input() -> impl Iterator<Item = (String, i32)> {
-        super::super::util::read_string_int_from_file("data/day2.txt")
+    fn puzzle_input() -> impl Iterator<Item = Command> {
+        super::super::util::parse_file("data/day2.txt", parse_line).expect("puzzle input")
     }
 
     #[test]
     fn test_part1() {
-        let (depth, horiz, distance) = part1(test_data());
+        let (depth, horiz, distance) = part1(test_data()).unwrap();
 
         assert_eq!(10, depth);
         assert_eq!(15, horiz);
         assert_eq!(150, distance);
 
-        let (depth, horiz, distance) = part1(puzzle_input());
+        let (_, _, distance) = part1(puzzle_input()).unwrap();
 
-        println!(
-            "day2 part 1: depth={}, horizontal={}, distance={}",
-            depth, horiz, distance
-        );
+        verify(2, Part::One, "day2.txt", distance);
     }
 
     #[test]
     fn test_part2() {
-        let (depth, horiz, distance, aim) = part2(test_data());
+        let (depth, horiz, distance, aim) = part2(test_data()).unwrap();
 
         assert_eq!(60, depth);
         assert_eq!(15, horiz);
         assert_eq!(900, distance);
         assert_eq!(10, aim);
 
-        let (depth, horiz, distance, aim) = part2(puzzle_input());
+        let (_, _, distance, _) = part2(puzzle_input()).unwrap();
+
+        verify(2, Part::Two, "day2.txt", distance);
+    }
+
+    #[test]
+    fn test_unknown_direction() {
+        assert_matches!(
+            Command::parse("sideways 2"),
+            Err(Error::Parse { column: 1, .. })
+        );
+        assert_matches!(
+            Command::parse("  backwards 2"),
+            Err(Error::Parse { column: 3, .. })
+        );
+        assert_matches!(
+            Command::parse("forward two"),
+            Err(Error::Parse { column: 9, .. })
+        );
+        assert_matches!(Command::parse("forward"), Err(Error::Parse { .. }));
+    }
+
+    #[test]
+    fn test_validate() {
+        let problems = |raw: &str| -> Vec<String> {
+            Day2::validate(raw).iter().map(|e| e.to_string()).collect()
+        };
+        assert_eq!(
+            vec![
+                "line 2, column 1: unknown direction: sideways",
+                "line 3, column 9: not a number: two",
+                "line 5, column 1: unknown direction: repeats",
+            ],
+            problems("forward 1\nsideways 2\nforward two\nback 3\nrepeats 2\n")
+        );
+        assert!(problems("forward 1\nback 1\n").is_empty());
 
-        println!(
-            "day2 part 2: depth={}, horizontal={}, distance={}, aim={}",
-            depth, horiz, distance, aim
+        // a script stops at its first problem
+        assert_eq!(
+            vec!["line 2, column 1: unknown command: sideways"],
+            problems("repeat 2 { up 1 }\nsideways 2\nforward two\n")
         );
+        assert_eq!(vec!["input is empty"], problems("# nothing\n"));
+    }
+
+    #[test]
+    fn test_far_courses() {
+        let day = crate::day(2).unwrap();
+        let raw = "down 100000\nrepeat 100000 { forward 1000 }\n";
+        assert_eq!("10000000000000", day.run(raw, Part::One).unwrap());
+        assert_eq!("1000000000000000000000", day.run(raw, Part::Two).unwrap());
+
+        // the depth runs out of room, even in 64 bits
+        let raw = "down 2147483647\nrepeat 10 { forward 2147483647 }\n";
+        assert_matches!(day.run(raw, Part::Two), Err(Error::InvalidState(_)));
+        let mut submarine = Submarine {
+            horiz: i64::MAX,
+            ..Submarine::default()
+        };
+        assert_matches!(
+            Direct.apply(&mut submarine, Command::Forward(1)),
+            Err(Error::InvalidState(_))
+        );
+        // and a failed command leaves the submarine where it was
+        assert_eq!(i64::MAX, submarine.horiz);
+        assert_matches!(
+            Aim.apply(&mut submarine, Command::Forward(1)),
+            Err(Error::InvalidState(_))
+        );
+        assert_eq!(
+            Submarine {
+                horiz: i64::MAX,
+                ..Submarine::default()
+            },
+            submarine
+        );
+    }
+
+    #[test]
+    fn test_models() {
+        let course: Vec<Command> = test_data().collect();
+        for command in course.iter() {
+            assert_eq!(*command, Command::parse(&command.to_string()).unwrap());
+        }
+
+        // a model that ignores up, say for a broken ballast tank
+        let sinking = |s: &mut Submarine, c: Command| match c {
+            Command::Up(_) => Ok(()),
+            c => Direct.apply(s, c),
+        };
+        let ends = compare(&[&Direct, &Aim, &sinking], &course).unwrap();
+        assert_eq!(navigate(&Direct, course.iter().copied()).unwrap(), ends[0]);
+        assert_eq!(
+            Submarine {
+                depth: 60,
+                horiz: 15,
+                aim: 10
+            },
+            ends[1]
+        );
+        assert_eq!((13, 15), (ends[2].depth, ends[2].horiz));
+
+        let mut navigator = Navigator::with_model(sinking);
+        navigator.push("down 3").unwrap();
+        navigator.push("up 3").unwrap();
+        assert_eq!(3, navigator.submarine.depth);
     }
 }
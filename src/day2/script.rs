@@ -0,0 +1,423 @@
+//! Courses written as scripts rather than one command per line. On top of
+//! the commands themselves a script can have `# comments`, blocks repeated
+//! with `repeat N { ... }` and named blocks defined with `macro NAME { ... }`
+//! and used by name:
+//!
+//! ```text
+//! # down the trench and back out
+//! macro dip {
+//!     down 2
+//!     forward 1
+//!     up 2
+//! }
+//! repeat 3 { dip forward 4 }
+//! back 5
+//! ```
+//!
+//! A macro is defined at the top level, before it is used. A plain course is
+//! a script too, though day 2 only reads one as a script if [`is_script`]. A script can't stand for more than `MAX_COMMANDS` commands.
+
+use super::Command;
+use crate::error::{Error, Result};
+use std::collections::BTreeMap;
+
+/// One thing for the submarine to do
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Statement {
+    Move(Command),
+    Repeat {
+        count: u32,
+        body: Vec<Statement>,
+    },
+    /// the statements of the macro with this name
+    Call(String),
+}
+
+/// A parsed script, which expands to a course
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Script {
+    pub macros: BTreeMap<String, Vec<Statement>>,
+    pub body: Vec<Statement>,
+}
+
+/// a word or brace, and where it was
+#[derive(Clone, Copy, Debug)]
+struct Token<'a> {
+    text: &'a str,
+    line: usize,
+    column: usize,
+}
+
+impl Token<'_> {
+    fn error(&self, message: impl Into<String>) -> Error {
+        Error::parse(self.line, self.column, message)
+    }
+}
+
+fn tokens(raw: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    for (i, line) in raw.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("");
+        let mut start = None;
+        for (j, c) in line.char_indices().chain([(line.len(), ' ')]) {
+            if c.is_whitespace() || c == '{' || c == '}' {
+                if let Some(s) = start.take() {
+                    tokens.push(Token {
+                        text: &line[s..j],
+                        line: i + 1,
+                        column: s + 1,
+                    });
+                }
+                if c == '{' || c == '}' {
+                    tokens.push(Token {
+                        text: &line[j..j + 1],
+                        line: i + 1,
+                        column: j + 1,
+                    });
+                }
+            } else if start.is_none() {
+                start = Some(j);
+            }
+        }
+    }
+    tokens
+}
+
+/// Count `commands` more (`None` being too many to count) for `token`, so a
+/// script that would expand to too many is stopped where it does
+fn grow(length: usize, token: Token, commands: Option<usize>) -> Result<usize> {
+    commands
+        .and_then(|n| n.checked_add(length))
+        .filter(|&n| n <= MAX_COMMANDS)
+        .ok_or_else(|| token.error(format!("expands to more than {} commands", MAX_COMMANDS)))
+}
+
+/// Whether a course needs reading as a script: it has a comment, a brace or
+/// a line starting with `repeat` or `macro`
+/// ```
+/// use aoc2021::day2::script::is_script;
+///
+/// assert!(!is_script("forward 5\nback 2"));
+/// assert!(is_script("forward 5  # ahead"));
+/// assert!(is_script("repeat 2"));
+/// ```
+pub fn is_script(raw: &str) -> bool {
+    raw.lines().any(|line| {
+        line.contains(['#', '{', '}'])
+            || matches!(line.split_whitespace().next(), Some("repeat" | "macro"))
+    })
+}
+
+const KEYWORDS: [&str; 6] = ["forward", "up", "down", "back", "repeat", "macro"];
+
+/// The most commands a script can expand to, which is a thousand times the
+/// size of a real course
+pub const MAX_COMMANDS: usize = 1_000_000;
+
+struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    next: usize,
+    macros: BTreeMap<String, Vec<Statement>>,
+    /// how many commands each macro expands to
+    lengths: BTreeMap<String, usize>,
+}
+
+impl<'a> Parser<'a> {
+    fn take(&mut self) -> Option<Token<'a>> {
+        let token = self.tokens.get(self.next).copied();
+        self.next += 1;
+        token
+    }
+
+    /// the token after `keyword`, which has to be on the same line
+    fn argument(&mut self, keyword: Token<'a>, what: &str) -> Result<Token<'a>> {
+        match self.tokens.get(self.next) {
+            Some(&token) if token.line == keyword.line => {
+                self.next += 1;
+                Ok(token)
+            }
+            _ => Err(keyword.error(format!("{} needs {}", keyword.text, what))),
+        }
+    }
+
+    fn number<T: std::str::FromStr>(&mut self, keyword: Token<'a>) -> Result<T> {
+        let token = self.argument(keyword, "a number")?;
+        token
+            .text
+            .parse()
+            .map_err(|_| token.error(format!("not a number: {}", token.text)))
+    }
+
+    /// The statements up to the `}` matching `open`, or to the end of the
+    /// script when there is no `open`, and how many commands they expand to
+    fn block(&mut self, open: Option<Token<'a>>) -> Result<(Vec<Statement>, usize)> {
+        let mut statements = Vec::new();
+        let mut length = 0;
+        loop {
+            let token = match self.take() {
+                Some(token) => token,
+                None => match open {
+                    Some(open) => return Err(open.error("{ is never closed")),
+                    None => return Ok((statements, length)),
+                },
+            };
+            let statement = match token.text {
+                "}" if open.is_some() => return Ok((statements, length)),
+                "}" => return Err(token.error("} without a {")),
+                "{" => return Err(token.error("{ without a repeat or macro")),
+                "forward" => Command::Forward(self.number(token)?),
+                "up" => Command::Up(self.number(token)?),
+                "down" => Command::Down(self.number(token)?),
+                "back" => Command::Back(self.number(token)?),
+                "repeat" => {
+                    let count: u32 = self.number(token)?;
+                    let (body, body_length) =
+                        self.open(token).and_then(|open| self.block(Some(open)))?;
+                    length = grow(length, token, body_length.checked_mul(count as usize))?;
+                    statements.push(Statement::Repeat { count, body });
+                    continue;
+                }
+                "macro" if open.is_some() => {
+                    return Err(token.error("macros are defined at the top level"))
+                }
+                "macro" => {
+                    self.define(token)?;
+                    continue;
+                }
+                name if self.macros.contains_key(name) => {
+                    length = grow(length, token, Some(self.lengths[name]))?;
+                    statements.push(Statement::Call(name.to_string()));
+                    continue;
+                }
+                name => return Err(token.error(format!("unknown command: {}", name))),
+            };
+            length = grow(length, token, Some(1))?;
+            statements.push(Statement::Move(statement));
+        }
+    }
+
+    /// the `{` starting the block of `keyword`
+    fn open(&mut self, keyword: Token<'a>) -> Result<Token<'a>> {
+        let token = self.argument(keyword, "a {")?;
+        match token.text {
+            "{" => Ok(token),
+            _ => Err(token.error(format!("expected {{, found {}", token.text))),
+        }
+    }
+
+    fn define(&mut self, keyword: Token<'a>) -> Result<()> {
+        let name = self.argument(keyword, "a name")?;
+        if KEYWORDS.contains(&name.text) || name.text == "{" || name.text == "}" {
+            return Err(name.error(format!("not a macro name: {}", name.text)));
+        }
+        if self.macros.contains_key(name.text) {
+            return Err(name.error(format!("{} is already defined", name.text)));
+        }
+        let (body, length) = self.open(name).and_then(|open| self.block(Some(open)))?;
+        self.macros.insert(name.text.to_string(), body);
+        self.lengths.insert(name.text.to_string(), length);
+        Ok(())
+    }
+}
+
+impl Script {
+    /// Parse a whole script, stopping at the first problem
+    /// ```
+    /// use aoc2021::day2::script::Script;
+    ///
+    /// let e = Script::parse("forward 2\nrepeat 2 {\n  sideways 1\n}").unwrap_err();
+    /// assert_eq!("line 3, column 3: unknown command: sideways", e.to_string());
+    /// ```
+    pub fn parse(raw: &str) -> Result<Script> {
+        let mut parser = Parser {
+            tokens: tokens(raw),
+            next: 0,
+            macros: BTreeMap::new(),
+            lengths: BTreeMap::new(),
+        };
+        let (body, _) = parser.block(None)?;
+        Ok(Script {
+            macros: parser.macros,
+            body,
+        })
+    }
+
+    /// The course the script describes, with every repeat and macro
+    /// written out
+    /// ```
+    /// use aoc2021::day2::script::Script;
+    /// use aoc2021::day2::Command::*;
+    ///
+    /// let script = Script::parse("macro dip { down 1 up 1 }\nrepeat 2 { dip }  # twice").unwrap();
+    /// assert_eq!(vec![Down(1), Up(1), Down(1), Up(1)], script.expand());
+    /// ```
+    pub fn expand(&self) -> Vec<Command> {
+        let mut commands = Vec::new();
+        self.expand_into(&self.body, &mut commands);
+        commands
+    }
+
+    fn expand_into(&self, statements: &[Statement], commands: &mut Vec<Command>) {
+        for statement in statements {
+            match statement {
+                Statement::Move(command) => commands.push(*command),
+                Statement::Repeat { count, body } => {
+                    for _ in 0..*count {
+                        self.expand_into(body, commands);
+                    }
+                }
+                // a macro can only use the ones defined before it, so this ends
+                Statement::Call(name) => self.expand_into(&self.macros[name], commands),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::day2::{navigate, Aim, Direct};
+    use Command::*;
+    use Statement::Move;
+
+    const EXAMPLE: &str = "\
+# down the trench and back out
+macro dip {
+    down 2
+    forward 1
+    up 2
+}
+repeat 3 { dip forward 4 }
+back 5
+";
+
+    #[test]
+    fn test_parse() {
+        let script = Script::parse(EXAMPLE).unwrap();
+        assert_eq!(
+            vec![Move(Down(2)), Move(Forward(1)), Move(Up(2))],
+            script.macros["dip"]
+        );
+        assert_eq!(
+            vec![
+                Statement::Repeat {
+                    count: 3,
+                    body: vec![Statement::Call("dip".to_string()), Move(Forward(4))]
+                },
+                Move(Back(5))
+            ],
+            script.body
+        );
+
+        // a plain course is a script with nothing but moves
+        let raw = std::fs::read_to_string("data/day2_test.txt").unwrap();
+        let plain: Vec<Command> = raw.lines().map(|l| Command::parse(l).unwrap()).collect();
+        assert_eq!(plain, Script::parse(&raw).unwrap().expand());
+        assert_eq!(Script::default(), Script::parse("\n  # nothing\n").unwrap());
+    }
+
+    #[test]
+    fn test_expand() {
+        let commands = Script::parse(EXAMPLE).unwrap().expand();
+        assert_eq!(13, commands.len());
+        assert_eq!(
+            vec![Down(2), Forward(1), Up(2), Forward(4), Down(2)],
+            commands[..5]
+        );
+
+        assert_eq!((10, 0), {
+            let s = navigate(&Direct, commands.iter().copied()).unwrap();
+            (s.horiz, s.depth)
+        });
+        // back reverses along the aim, so this comes back to the surface
+        let there_and_back = Script::parse("down 1 forward 3 back 3").unwrap();
+        assert_eq!((0, 0), {
+            let s = navigate(&Aim, there_and_back.expand()).unwrap();
+            (s.horiz, s.depth)
+        });
+
+        let nested =
+            "macro a { up 1 }\nmacro b { a a }\nrepeat 2 { repeat 3 { b } }\nrepeat 0 { a }";
+        assert_eq!(vec![Up(1); 12], Script::parse(nested).unwrap().expand());
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = |raw: &str| Script::parse(raw).unwrap_err().to_string();
+
+        assert_eq!(
+            "line 2, column 1: forward needs a number",
+            error("up 1\nforward\ndown 3")
+        );
+        assert_eq!("line 1, column 6: not a number: x", error("down x"));
+        assert_eq!(
+            "line 1, column 8: not a number: -1",
+            error("repeat -1 { up 1 }")
+        );
+        assert_eq!(
+            "line 1, column 1: repeat needs a {",
+            error("repeat 2\n{ up 1 }")
+        );
+        assert_eq!(
+            "line 1, column 10: expected {, found up",
+            error("repeat 2 up 1")
+        );
+        assert_eq!(
+            "line 2, column 10: { is never closed",
+            error("up 1\nrepeat 2 {\nup 1\n")
+        );
+        assert_eq!("line 1, column 6: } without a {", error("up 1 }"));
+        assert_eq!(
+            "line 1, column 1: { without a repeat or macro",
+            error("{ up 1 }")
+        );
+        assert_eq!(
+            "line 1, column 7: not a macro name: up",
+            error("macro up { down 1 }")
+        );
+        assert_eq!(
+            "line 2, column 7: a is already defined",
+            error("macro a { up 1 }\nmacro a { up 2 }")
+        );
+        assert_eq!(
+            "line 1, column 12: macros are defined at the top level",
+            error("repeat 2 { macro a { up 1 } }")
+        );
+        // macros are used after they are defined, so can't call themselves
+        assert_eq!(
+            "line 1, column 11: unknown command: a",
+            error("macro a { a }")
+        );
+        assert_eq!(
+            "line 1, column 1: unknown command: b",
+            error("b\nmacro b { up 1 }")
+        );
+        // too long to write out, even when it's short to write down
+        assert_eq!(
+            "line 4, column 1: expands to more than 1000000 commands",
+            error(
+                "macro a { forward 1 }\nmacro b { a a }\nmacro c { b b }\nrepeat 4000000000 { c }"
+            )
+        );
+        let doubling: String = (1..30)
+            .map(|i| format!("macro m{} {{ m{} m{} }}\n", i, i - 1, i - 1))
+            .collect();
+        assert_eq!(
+            "line 21, column 17: expands to more than 1000000 commands",
+            error(&format!("macro m0 {{ up 1 }}\n{}", doubling))
+        );
+        assert_eq!(
+            MAX_COMMANDS,
+            Script::parse("repeat 1000 { repeat 1000 { up 1 } }")
+                .unwrap()
+                .expand()
+                .len()
+        );
+        // comments can't hide a brace
+        assert_eq!(
+            "line 1, column 10: { is never closed",
+            error("repeat 2 { # }")
+        );
+    }
+}
@@ -0,0 +1,291 @@
+//! Where the submarine goes along the way, not just where it ends up, and
+//! pictures of it.
+
+use super::{Command, Model, Submarine};
+use crate::error::Result;
+
+/// The state of the submarine just after a command
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub command: Command,
+    pub submarine: Submarine,
+}
+
+/// Follows a course lazily, one step per command, stopping after the
+/// first command the model can't apply
+pub struct Trajectory<'m, M: ?Sized, I> {
+    model: &'m M,
+    commands: I,
+    submarine: Submarine,
+    failed: bool,
+}
+
+impl<M: Model + ?Sized, I: Iterator<Item = Command>> Iterator for Trajectory<'_, M, I> {
+    type Item = Result<Step>;
+
+    fn next(&mut self) -> Option<Result<Step>> {
+        if self.failed {
+            return None;
+        }
+        let command = self.commands.next()?;
+        if let Err(e) = self.model.apply(&mut self.submarine, command) {
+            self.failed = true;
+            return Some(Err(e));
+        }
+        Some(Ok(Step {
+            command,
+            submarine: self.submarine,
+        }))
+    }
+}
+
+/// Every step of a course from the surface
+/// ```
+/// use aoc2021::day2::{trajectory, Aim, Command};
+///
+/// let course = [Command::Down(5), Command::Forward(2), Command::Up(1)];
+/// let depths: Vec<i64> = trajectory::trajectory(&Aim, course)
+///     .map(|step| step.unwrap().submarine.depth)
+///     .collect();
+/// assert_eq!(vec![0, 10, 10], depths);
+/// ```
+pub fn trajectory<M: Model + ?Sized, C: IntoIterator<Item = Command>>(
+    model: &M,
+    commands: C,
+) -> Trajectory<'_, M, C::IntoIter> {
+    Trajectory {
+        model,
+        commands: commands.into_iter(),
+        submarine: Submarine::default(),
+        failed: false,
+    }
+}
+
+/// The surface and then the state after each command, ready to be drawn
+pub fn course(
+    model: &(impl Model + ?Sized),
+    commands: impl IntoIterator<Item = Command>,
+) -> Result<Vec<Submarine>> {
+    std::iter::once(Ok(Submarine::default()))
+        .chain(trajectory(model, commands).map(|step| Ok(step?.submarine)))
+        .collect()
+}
+
+/// the smallest and largest of some values, wide enough to subtract
+fn extent(values: impl Iterator<Item = i64>) -> (i128, i128) {
+    values.fold((0, 0), |(lo, hi), v| (lo.min(v as i128), hi.max(v as i128)))
+}
+
+/// `value` from `lo..=hi` scaled to `0..=cells - 1`
+fn scale(value: i64, (lo, hi): (i128, i128), cells: usize) -> usize {
+    let span = (hi - lo).max(1);
+    (((value as i128 - lo) * (cells as i128 - 1) + span / 2) / span) as usize
+}
+
+/// A side view of the course, `columns` wide by `rows` deep, with the
+/// surface at the top. The top and bottom rows are labelled with their
+/// depths.
+/// ```
+/// use aoc2021::day2::{trajectory, Command, Direct};
+///
+/// let course = trajectory::course(&Direct, [
+///     Command::Forward(2),
+///     Command::Down(2),
+///     Command::Forward(2),
+/// ]).unwrap();
+/// assert_eq!("0 |***\n  |  *\n2 |  ***\n", trajectory::ascii_profile(&course, 5, 3));
+/// ```
+pub fn ascii_profile(course: &[Submarine], columns: usize, rows: usize) -> String {
+    let (columns, rows) = (columns.max(1), rows.max(1));
+    let across = extent(course.iter().map(|s| s.horiz));
+    let down = extent(course.iter().map(|s| s.depth));
+    let cell = |s: &Submarine| (scale(s.horiz, across, columns), scale(s.depth, down, rows));
+
+    let mut grid = vec![vec![' '; columns]; rows];
+    let mut mark = |(x, y): (usize, usize)| grid[y][x] = '*';
+    if let Some(first) = course.first() {
+        mark(cell(first));
+    }
+    // fill in between the steps, so the line doesn't have gaps
+    for pair in course.windows(2) {
+        let ((x0, y0), (x1, y1)) = (cell(&pair[0]), cell(&pair[1]));
+        let steps = x0.abs_diff(x1).max(y0.abs_diff(y1)).max(1);
+        for i in 0..=steps {
+            let along = |a: usize, b: usize| {
+                let a = a as i64;
+                let b = b as i64;
+                (a + ((b - a) * i as i64 * 2 + steps as i64).div_euclid(steps as i64 * 2)) as usize
+            };
+            mark((along(x0, x1), along(y0, y1)));
+        }
+    }
+
+    let top = down.0.to_string();
+    let bottom = down.1.to_string();
+    let width = top.len().max(bottom.len());
+    let mut out = String::new();
+    for (i, row) in grid.iter().enumerate() {
+        let label = match i {
+            0 => top.as_str(),
+            i if i == rows - 1 => bottom.as_str(),
+            _ => "",
+        };
+        let line: String = row.iter().collect();
+        out += format!("{:<width$} |{}", label, line.trim_end(), width = width).as_str();
+        out.push('\n');
+    }
+    out
+}
+
+/// The course as an SVG picture `width` by `height`, depth going down the
+/// page
+/// ```
+/// use aoc2021::day2::{trajectory, Command, Direct};
+///
+/// let course = trajectory::course(&Direct, [Command::Forward(4), Command::Down(2)]).unwrap();
+/// let svg = trajectory::svg_polyline(&course, 100, 50);
+/// assert!(svg.contains(r#"points="0,0 100,0 100,50""#));
+/// ```
+pub fn svg_polyline(course: &[Submarine], width: u32, height: u32) -> String {
+    let across = extent(course.iter().map(|s| s.horiz));
+    let down = extent(course.iter().map(|s| s.depth));
+    let position = |value: i64, (lo, hi): (i128, i128), size: u32| {
+        (value as i128 - lo) as f64 * size as f64 / (hi - lo).max(1) as f64
+    };
+    let points: Vec<String> = course
+        .iter()
+        .map(|s| {
+            format!(
+                "{},{}",
+                position(s.horiz, across, width),
+                position(s.depth, down, height)
+            )
+        })
+        .collect();
+    format!(
+        concat!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+            r#"<polyline fill="none" stroke="black" points="{points}"/>"#,
+            "</svg>\n"
+        ),
+        w = width,
+        h = height,
+        points = points.join(" ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::day2::{navigate, Aim, Direct};
+    use crate::error::Error;
+
+    fn example() -> Vec<Command> {
+        [
+            "forward 5",
+            "down 5",
+            "forward 8",
+            "up 3",
+            "down 8",
+            "forward 2",
+        ]
+        .iter()
+        .map(|line| Command::parse(line).unwrap())
+        .collect()
+    }
+
+    #[test]
+    fn test_trajectory() {
+        let steps: Vec<Step> = trajectory(&Aim, example()).map(Result::unwrap).collect();
+        assert_eq!(6, steps.len());
+        assert_eq!(
+            example(),
+            steps.iter().map(|s| s.command).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![
+                (5, 0, 0),
+                (5, 0, 5),
+                (13, 40, 5),
+                (13, 40, 2),
+                (13, 40, 10),
+                (15, 60, 10)
+            ],
+            steps
+                .iter()
+                .map(|s| (s.submarine.horiz, s.submarine.depth, s.submarine.aim))
+                .collect::<Vec<_>>()
+        );
+        // it ends where navigating does
+        for model in [&Direct as &dyn Model, &Aim] {
+            assert_eq!(
+                navigate(model, example()).ok(),
+                trajectory(model, example())
+                    .last()
+                    .map(|s| s.unwrap().submarine)
+            );
+        }
+
+        let surfaced = course(&Direct, example()).unwrap();
+        assert_eq!(7, surfaced.len());
+        assert_eq!(Submarine::default(), surfaced[0]);
+
+        // it stops at the first command that goes too far
+        let steep = [
+            Command::Down(i32::MAX),
+            Command::Forward(i32::MAX),
+            Command::Forward(i32::MAX),
+            Command::Forward(i32::MAX),
+            Command::Forward(1),
+        ];
+        let steps: Vec<_> = trajectory(&Aim, steep).collect();
+        assert_eq!(4, steps.len());
+        assert!(steps[..3].iter().all(|s| s.is_ok()));
+        assert_matches!(steps[3], Err(Error::InvalidState(_)));
+        assert_matches!(course(&Aim, steep), Err(Error::InvalidState(_)));
+    }
+
+    #[test]
+    fn test_ascii_profile() {
+        let example = course(&Aim, example()).unwrap();
+        assert_eq!(
+            concat!(
+                "0  |******\n",
+                "   |      *\n",
+                "   |       **\n",
+                "   |         *\n",
+                "   |          *\n",
+                "   |           **\n",
+                "   |             *\n",
+                "   |              *\n",
+                "   |              *\n",
+                "60 |               *\n",
+            ),
+            ascii_profile(&example, 16, 10)
+        );
+        // climbing back up
+        let dive = [
+            Command::Down(1),
+            Command::Forward(3),
+            Command::Up(2),
+            Command::Forward(3),
+        ];
+        assert_eq!(
+            "0 |*     *\n  | *   *\n  |  * *\n3 |   *\n",
+            ascii_profile(&course(&Aim, dive).unwrap(), 7, 4)
+        );
+        // nothing but the surface
+        assert_eq!("0 |*\n", ascii_profile(&[Submarine::default()], 4, 1));
+        assert_eq!("0 |\n", ascii_profile(&[], 4, 1));
+    }
+
+    #[test]
+    fn test_svg_polyline() {
+        let course = course(&Direct, example()).unwrap();
+        let svg = svg_polyline(&course, 150, 100);
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.contains(r#"width="150" height="100""#));
+        assert!(svg.contains(r#"points="0,0 50,0 50,50 130,50 130,20 130,100 150,100""#));
+    }
+}
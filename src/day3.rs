@@ -1,197 +1,404 @@
-fn reset_counts(input: Vec<&Vec<u8>>, v0: &mut Vec<u32>, v1: &mut Vec<u32>) {
-    for i in 0..v0.len() {
-        v0[i] = 0;
-        v1[i] = 0;
-    }
-
-    input.iter().for_each(|row| {
-        for (i, v) in row.iter().enumerate() {
-            match v {
-                0 => v0[i] += 1,
-                1 => v1[i] += 1,
-                _ => unreachable!(),
-            }
-        }
-    });
+pub mod number;
+
+use crate::error::{Error, Result};
+use crate::report::Report;
+use crate::solution::Solution;
+use number::Number;
+
+/// A fixed number of bits packed 64 to a word, such as a row of the report,
+/// or a column of it with a bit for each row
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Bits {
+    /// bit `i` is bit `i % 64` of word `i / 64`, and the bits past the end
+    /// are always 0
+    words: Vec<u64>,
+    len: usize,
 }
 
-pub fn calculate_power(input: Vec<Vec<u8>>) -> (u128, u128, u128) {
-    let mut gamma: String = String::new();
-    let mut epsilon: String = String::new();
+impl Bits {
+    pub fn zeros(len: usize) -> Self {
+        Bits {
+            words: vec![0; len.div_ceil(64)],
+            len,
+        }
+    }
 
-    let size: usize = input[0].len();
+    pub fn ones(len: usize) -> Self {
+        let mut bits = Bits {
+            words: vec![u64::MAX; len.div_ceil(64)],
+            len,
+        };
+        bits.clear_tail();
+        bits
+    }
 
-    let mut v0: Vec<u32> = vec![0; size];
-    let mut v1: Vec<u32> = vec![0; size];
+    fn clear_tail(&mut self) {
+        if !self.len.is_multiple_of(64) {
+            if let Some(last) = self.words.last_mut() {
+                *last &= (1 << (self.len % 64)) - 1;
+            }
+        }
+    }
 
-    for row in input.iter() {
-        for (i, v) in row.iter().enumerate() {
-            match v {
-                0 => v0[i] += 1,
-                1 => v1[i] += 1,
-                _ => unreachable!(),
+    /// Parse a line of 0s and 1s, however long it is
+    /// ```
+    /// use aoc2021::day3::Bits;
+    ///
+    /// let bits = Bits::parse("00100").unwrap();
+    /// assert_eq!((5, 1), (bits.len(), bits.count_ones()));
+    /// assert!(bits.get(2));
+    ///
+    /// let e = Bits::parse("0012").unwrap_err();
+    /// assert_eq!("column 4: not a bit: 2", e.to_string());
+    /// ```
+    pub fn parse(line: &str) -> Result<Self> {
+        let trimmed = line.trim();
+        let indent = line.len() - line.trim_start().len();
+        let mut bits = Bits::zeros(trimmed.len());
+        for (i, c) in trimmed.char_indices() {
+            match c {
+                '0' => (),
+                '1' => bits.set(i, true),
+                _ => {
+                    return Err(Error::parse_line(
+                        indent + i + 1,
+                        format!("not a bit: {}", c),
+                    ))
+                }
             }
         }
+        Ok(bits)
     }
 
-    for i in 0..size {
-        if v0[i] > v1[i] {
-            gamma.push('0');
-            epsilon.push('1');
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, i: usize) -> bool {
+        assert!(i < self.len, "bit {} of {}", i, self.len);
+        self.words[i / 64] >> (i % 64) & 1 == 1
+    }
+
+    pub fn set(&mut self, i: usize, value: bool) {
+        assert!(i < self.len, "bit {} of {}", i, self.len);
+        if value {
+            self.words[i / 64] |= 1 << (i % 64);
         } else {
-            gamma.push('1');
-            epsilon.push('0');
+            self.words[i / 64] &= !(1 << (i % 64));
         }
     }
 
-    let gv = u128::from_str_radix(&gamma, 2).unwrap();
-    let ev = u128::from_str_radix(&epsilon, 2).unwrap();
-    let power = gv * ev;
-    (gv, ev, power)
-}
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// how many bits are set in both, without making the intersection
+    pub fn count_ones_and(&self, other: &Bits) -> usize {
+        self.words
+            .iter()
+            .zip(other.words.iter())
+            .map(|(a, b)| (a & b).count_ones() as usize)
+            .sum()
+    }
 
-fn vec_to_string(input: &[u8]) -> String {
-    let mut output: String = String::new();
-    for v in input.iter() {
-        match v {
-            0 => output.push('0'),
-            1 => output.push('1'),
-            _ => unreachable!(),
+    /// Keep only the bits that are also set in `other`
+    pub fn and(&mut self, other: &Bits) {
+        for (a, b) in self.words.iter_mut().zip(other.words.iter()) {
+            *a &= b;
         }
     }
-    output
-}
 
-pub fn calc_generators(input: Vec<Vec<u8>>) -> (u128, u128, u128) {
-    let size: usize = input[0].len();
+    /// Keep only the bits that are not set in `other`
+    pub fn and_not(&mut self, other: &Bits) {
+        for (a, b) in self.words.iter_mut().zip(other.words.iter()) {
+            *a &= !b;
+        }
+    }
 
-    let mut v0: Vec<u32> = vec![0; size];
-    let mut v1: Vec<u32> = vec![0; size];
+    /// every bit flipped
+    pub fn not(&self) -> Bits {
+        let mut bits = Bits {
+            words: self.words.iter().map(|w| !w).collect(),
+            len: self.len,
+        };
+        bits.clear_tail();
+        bits
+    }
 
-    // need a vector of references
-    reset_counts(input.iter().collect(), &mut v0, &mut v1);
+    /// the positions of the bits that are set, in order
+    pub fn iter_ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &word)| {
+            let mut left = word;
+            std::iter::from_fn(move || {
+                if left == 0 {
+                    return None;
+                }
+                let bit = left.trailing_zeros() as usize;
+                left &= left - 1;
+                Some(i * 64 + bit)
+            })
+        })
+    }
+}
 
-    let mut o2: Vec<&Vec<u8>> = input
-        .iter()
-        .filter(|row| {
-            let want = if v0[0] > v1[0] { 0 } else { 1 };
-            if row[0] != want {
-                return false;
+/// The report both ways round: the rows, and a bitset per column with a
+/// bit for each row, so counting the 1s of a column in any set of rows is a
+/// popcount
+pub struct Diagnostic {
+    rows: Vec<Bits>,
+    columns: Vec<Bits>,
+}
+
+impl Diagnostic {
+    pub fn new(rows: Vec<Bits>) -> Result<Self> {
+        let width = match rows.first() {
+            Some(row) => row.len(),
+            None => return Err(Error::invalid("no diagnostic report rows")),
+        };
+        let mut columns = vec![Bits::zeros(rows.len()); width];
+        for (i, row) in rows.iter().enumerate() {
+            if row.len() != width {
+                return Err(Error::invalid(format!(
+                    "expected {} bits, found {}",
+                    width,
+                    row.len()
+                )));
             }
-            true
-        })
-        .collect();
-
-    let mut co2: Vec<&Vec<u8>> = input
-        .iter()
-        .filter(|row| {
-            let want = if v0[0] > v1[0] { 1 } else { 0 };
-            if row[0] != want {
-                return false;
+            for column in row.iter_ones() {
+                columns[column].set(i, true);
             }
-            true
-        })
-        .collect();
+        }
+        Ok(Diagnostic { rows, columns })
+    }
+
+    pub fn width(&self) -> usize {
+        self.columns.len()
+    }
 
-    for i in 1..size {
-        // loop only until there is one left
+    /// every row, to narrow down from
+    pub fn all(&self) -> Bits {
+        Bits::ones(self.rows.len())
+    }
 
-        if o2.len() == 1 {
-            break;
+    /// how many of the `active` rows have a 1 in `column`
+    pub fn ones(&self, column: usize, active: &Bits) -> usize {
+        self.columns[column].count_ones_and(active)
+    }
+
+    /// The one row left after going through the columns keeping the rows
+    /// with a 1 when `keep_ones` says so given how many 1s and 0s there are
+    /// (and the rows with a 0 otherwise)
+    fn rating(&self, keep_ones: impl Fn(usize, usize) -> bool) -> Option<&Bits> {
+        let mut active = self.all();
+        for column in 0..self.width() {
+            let left = active.count_ones();
+            if left == 1 {
+                break;
+            }
+            let ones = self.ones(column, &active);
+            if keep_ones(ones, left - ones) {
+                active.and(&self.columns[column]);
+            } else {
+                active.and_not(&self.columns[column]);
+            }
+        }
+        match active.count_ones() {
+            1 => active.iter_ones().next().map(|i| &self.rows[i]),
+            _ => None,
         }
+    }
+}
 
-        reset_counts(o2.to_vec(), &mut v0, &mut v1);
+pub fn calculate_power(input: Vec<Bits>) -> Result<(Number, Number, Number)> {
+    let report = Diagnostic::new(input)?;
+    let all = report.all();
+    let rows = all.count_ones();
 
-        o2 = o2
-            .iter()
-            .cloned()
-            .filter(|row| {
-                let want = if v0[i] > v1[i] { 0 } else { 1 };
-                if row[i] != want {
-                    return false;
-                }
-                true
-            })
-            .collect();
+    // the most common bit of each column, 1 on a tie
+    let mut gamma = Bits::zeros(report.width());
+    for column in 0..report.width() {
+        let ones = report.ones(column, &all);
+        gamma.set(column, ones >= rows - ones);
     }
+    let epsilon = gamma.not();
 
-    assert_eq!(o2.len(), 1);
+    let gv = Number::from_bits(&gamma);
+    let ev = Number::from_bits(&epsilon);
+    let power = &gv * &ev;
+    Ok((gv, ev, power))
+}
 
-    for i in 1..size {
-        if co2.len() == 1 {
-            break;
-        }
+pub fn calc_generators(input: Vec<Bits>) -> Result<(Number, Number, Number)> {
+    let report = Diagnostic::new(input)?;
 
-        // recalculate the index
-        reset_counts(co2.to_vec(), &mut v0, &mut v1);
+    let o2 = report
+        .rating(|ones, zeros| ones >= zeros)
+        .ok_or_else(|| Error::invalid("no single oxygen generator rating"))?;
+    let co2 = report
+        .rating(|ones, zeros| ones < zeros)
+        .ok_or_else(|| Error::invalid("no single CO2 scrubber rating"))?;
 
-        co2 = co2
-            .iter()
-            .cloned()
-            .filter(|row| {
-                let want = if v0[i] > v1[i] { 1 } else { 0 };
-                // println!("want: {} in ix {}", want, i);
-                if row[i] != want {
-                    return false;
-                }
-                true
-            })
-            .collect();
+    let ov = Number::from_bits(o2);
+    let cv = Number::from_bits(co2);
+    let rating = &ov * &cv;
+    Ok((ov, cv, rating))
+}
+
+/// Parse a row such as "10110"
+pub fn parse_line(line: String) -> Result<Bits> {
+    Bits::parse(line.as_str())
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u8 = 3;
+
+    type Input = Vec<Bits>;
+    type Answer1 = Number;
+    type Answer2 = Number;
+
+    fn parse(raw: &str) -> Result<Self::Input> {
+        super::util::parse_lines(raw, parse_line)
+    }
+
+    fn validate(raw: &str) -> Vec<Error> {
+        super::util::check_grid(raw, |c| c == '0' || c == '1')
+    }
+
+    fn part1(input: Self::Input) -> Result<Self::Answer1> {
+        calculate_power(input).map(|r| r.2)
+    }
+
+    fn part2(input: Self::Input) -> Result<Self::Answer2> {
+        calc_generators(input).map(|r| r.2)
     }
-    assert_eq!(co2.len(), 1);
 
-    let ov = u128::from_str_radix(&vec_to_string(o2[0]), 2).unwrap();
-    let cv = u128::from_str_radix(&vec_to_string(co2[0]), 2).unwrap();
-    let rating = ov * cv;
-    (ov, cv, rating)
+    fn report1(input: Self::Input) -> Result<Report> {
+        let (gamma, epsilon, power) = calculate_power(input)?;
+        Ok(Report::new(&power)
+            .with("gamma", gamma)
+            .with("epsilon", epsilon)
+            .with("power", power))
+    }
+
+    fn report2(input: Self::Input) -> Result<Report> {
+        let (oxygen, co2, life_support) = calc_generators(input)?;
+        Ok(Report::new(&life_support)
+            .with("oxygen", oxygen)
+            .with("co2", co2)
+            .with("life_support", life_support))
+    }
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::answers::verify;
+    use crate::solution::Part;
+
+    fn test_data() -> impl Iterator<Item = Bits> {
+        super::super::util::parse_file("data/day3_test.txt", parse_line).expect("test data")
+    }
 
-    fn test_data() -> impl Iterator<Item = Vec<u8>> {
-        super::super::util::read_int_list_from_file("data/day3_test.txt")
+    fn puzzle_input() -> impl Iterator<Item = Bits> {
+        super::super::util::parse_file("data/day3.txt", parse_line).expect("puzzle input")
     }
 
-    fn puzzle_input() -> impl Iterator<Item = Vec<u8>> {
-        super::super::util::read_int_list_from_file("data/day3.txt")
+    fn rows(lines: &[&str]) -> Vec<Bits> {
+        lines.iter().map(|l| Bits::parse(l).unwrap()).collect()
     }
 
     #[test]
     fn test_calc_gamma() {
-        let (gamma, epsilon, power) = calculate_power(test_data().collect());
-        assert_eq!(gamma, 22);
-        assert_eq!(epsilon, 9);
-        assert_eq!(power, 198);
+        let (gamma, epsilon, power) = calculate_power(test_data().collect()).unwrap();
+        assert_eq!(gamma, Number::from(22));
+        assert_eq!(epsilon, Number::from(9));
+        assert_eq!(power, Number::from(198));
 
-        let (gamma, epsilon, power) = calculate_power(puzzle_input().collect());
+        let (_, _, power) = calculate_power(puzzle_input().collect()).unwrap();
 
-        println!("gamma={}, epsilon={}, power={}", gamma, epsilon, power);
+        verify(3, Part::One, "day3.txt", power);
     }
 
     #[test]
     fn test_calc_generators() {
-        let (oxy, co2, rating) = calc_generators(test_data().collect());
+        let (oxy, co2, rating) = calc_generators(test_data().collect()).unwrap();
 
-        assert_eq!(oxy, 23);
-        assert_eq!(co2, 10);
-        assert_eq!(rating, 230);
+        assert_eq!(oxy, Number::from(23));
+        assert_eq!(co2, Number::from(10));
+        assert_eq!(rating, Number::from(230));
 
-        let (oxy, co2, rating) = calc_generators(puzzle_input().collect());
-        println!("o2={}, c02={}, power={}", oxy, co2, rating);
+        let (_, _, rating) = calc_generators(puzzle_input().collect()).unwrap();
+        verify(3, Part::Two, "day3.txt", rating);
     }
 
     #[test]
-    #[should_panic]
-    fn test_reset_counts() {
-        let mut v0: Vec<u32> = vec![0; 2];
-        let mut v1: Vec<u32> = vec![0; 2];
+    fn test_bits() {
+        assert_matches!(Bits::parse("12"), Err(Error::Parse { column: 2, .. }));
+        assert_matches!(Bits::parse("  0x"), Err(Error::Parse { column: 4, .. }));
+
+        // across the word boundary
+        let line = format!("1{}1{}", "0".repeat(63), "01".repeat(40));
+        let bits = Bits::parse(&line).unwrap();
+        assert_eq!(145, bits.len());
+        assert_eq!(42, bits.count_ones());
+        assert_eq!(
+            vec![0, 64, 66, 68],
+            bits.iter_ones().take(4).collect::<Vec<_>>()
+        );
+        assert_eq!(145 - 42, bits.not().count_ones());
+        assert_eq!(145, Bits::ones(145).count_ones());
+
+        let mut evens = Bits::zeros(145);
+        (0..145).step_by(2).for_each(|i| evens.set(i, true));
+        assert_eq!(42, bits.count_ones_and(&evens));
+        let mut odd = bits.clone();
+        odd.and_not(&evens);
+        assert_eq!(0, odd.count_ones());
+    }
 
-        let row = &vec![1, 2]; // 2 is invalid
-        let data = vec![row];
+    #[test]
+    fn test_wide_report() {
+        // gamma is 2^129, and epsilon one less
+        let top = format!("1{}", "0".repeat(129));
+        let (gamma, epsilon, power) = calculate_power(rows(&[&top, &top])).unwrap();
+        assert_eq!("680564733841876926926749214863536422912", gamma.to_string());
+        assert_eq!(
+            "680564733841876926926749214863536422911",
+            epsilon.to_string()
+        );
+        assert_eq!(
+            "463168356949264781694283940034751631412399373928720379230903586816788982136832",
+            power.to_string()
+        );
+
+        let day = crate::day(3).unwrap();
+        let raw = format!("{}\n{}\n{}\n", top, "1".repeat(130), "0".repeat(130));
+        assert!(day.validate(&raw).is_empty());
+        assert_eq!(
+            // 2^130 - 1 (the most 1s) times 0 (the fewest)
+            "0",
+            day.run(&raw, Part::Two).unwrap()
+        );
+    }
 
-        reset_counts(data, &mut v0, &mut v1);
+    #[test]
+    fn test_invalid_report() {
+        assert_matches!(calculate_power(vec![]), Err(Error::InvalidState(_)));
+        assert_matches!(
+            calc_generators(rows(&["10", "1"])),
+            Err(Error::InvalidState(_))
+        );
+        // every row the same leaves no least common bit to keep
+        assert_matches!(
+            calc_generators(rows(&["101", "101"])),
+            Err(Error::InvalidState(_))
+        );
     }
 }
@@ -0,0 +1,172 @@
+//! Unsigned numbers as wide as a diagnostic report, which can be wider than
+//! any integer type.
+
+use super::Bits;
+use crate::report::Value;
+use std::fmt;
+use std::ops::Mul;
+
+/// An unsigned number in 32 bit limbs, least significant first
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Number {
+    /// never ends in a zero limb, so zero has none
+    limbs: Vec<u32>,
+}
+
+impl Number {
+    /// The number `bits` spell out, the first being the most significant
+    /// ```
+    /// use aoc2021::day3::number::Number;
+    /// use aoc2021::day3::Bits;
+    ///
+    /// let bits = Bits::parse("10110").unwrap();
+    /// assert_eq!(Number::from(22), Number::from_bits(&bits));
+    /// ```
+    pub fn from_bits(bits: &Bits) -> Self {
+        let mut limbs = vec![0u32; bits.len().div_ceil(32)];
+        for i in bits.iter_ones() {
+            let place = bits.len() - 1 - i;
+            limbs[place / 32] |= 1 << (place % 32);
+        }
+        Number { limbs }.trimmed()
+    }
+
+    fn trimmed(mut self) -> Self {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+        self
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// the value, if it is small enough
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.limbs.len() > 4 {
+            return None;
+        }
+        Some(
+            self.limbs
+                .iter()
+                .rev()
+                .fold(0, |value, &limb| value << 32 | limb as u128),
+        )
+    }
+
+    /// Divide in place, returning the remainder
+    fn divide(&mut self, divisor: u32) -> u32 {
+        let mut remainder = 0u64;
+        for limb in self.limbs.iter_mut().rev() {
+            let value = remainder << 32 | *limb as u64;
+            *limb = (value / divisor as u64) as u32;
+            remainder = value % divisor as u64;
+        }
+        *self = std::mem::take(self).trimmed();
+        remainder as u32
+    }
+}
+
+impl From<u64> for Number {
+    fn from(value: u64) -> Self {
+        Number {
+            limbs: vec![value as u32, (value >> 32) as u32],
+        }
+        .trimmed()
+    }
+}
+
+impl Mul for &Number {
+    type Output = Number;
+
+    /// the long multiplication from school, a limb at a time
+    fn mul(self, other: &Number) -> Number {
+        let mut limbs = vec![0u32; self.limbs.len() + other.limbs.len()];
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, &b) in other.limbs.iter().enumerate() {
+                let value = a as u64 * b as u64 + limbs[i + j] as u64 + carry;
+                limbs[i + j] = value as u32;
+                carry = value >> 32;
+            }
+            limbs[i + other.limbs.len()] = carry as u32;
+        }
+        Number { limbs }.trimmed()
+    }
+}
+
+/// nine decimal digits at a time
+const CHUNK: u32 = 1_000_000_000;
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+        let mut left = self.clone();
+        let mut chunks = Vec::new();
+        while !left.is_zero() {
+            chunks.push(left.divide(CHUNK));
+        }
+        let mut chunks = chunks.iter().rev();
+        if let Some(first) = chunks.next() {
+            write!(f, "{}", first)?;
+        }
+        for chunk in chunks {
+            write!(f, "{:09}", chunk)?;
+        }
+        Ok(())
+    }
+}
+
+/// a JSON number when it fits, otherwise its digits in a string
+impl From<Number> for Value {
+    fn from(number: Number) -> Self {
+        match number.to_u128() {
+            Some(value) => Value::UInt(value),
+            None => Value::Str(number.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_multiply() {
+        let values = [
+            0u64,
+            1,
+            9,
+            1_000_000_000,
+            u32::MAX as u64,
+            1 << 40,
+            u64::MAX,
+        ];
+        for a in values {
+            for b in values {
+                let product = &Number::from(a) * &Number::from(b);
+                assert_eq!(Some(a as u128 * b as u128), product.to_u128());
+                assert_eq!((a as u128 * b as u128).to_string(), product.to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn test_wide() {
+        let bits = Bits::parse(&format!("1{}", "0".repeat(128))).unwrap();
+        let big = Number::from_bits(&bits);
+        assert_eq!(None, big.to_u128());
+        assert_eq!("340282366920938463463374607431768211456", big.to_string());
+        assert_eq!(
+            "115792089237316195423570985008687907853269984665640564039457584007913129639936",
+            (&big * &big).to_string()
+        );
+        assert_eq!(Value::Str(big.to_string()), Value::from(big));
+        assert_eq!(Value::UInt(7), Value::from(Number::from(7)));
+        assert_eq!("0", Number::from_bits(&Bits::zeros(70)).to_string());
+    }
+}
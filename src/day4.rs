@@ -1,3 +1,6 @@
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+
 // type aliases so that the template is easier
 
 type CalculationInput = (Vec<u8>, Vec<BingoCard>);
@@ -7,6 +10,7 @@ type DayResult = u64;
 
 // probably these should be references
 // but I haven't read that far in the book
+#[derive(Debug)]
 pub struct BingoCard {
     data: Vec<Vec<u8>>,
     checked: Vec<Vec<bool>>,
@@ -109,7 +113,7 @@ impl BingoCardBuilder {
         }
     }
 
-    pub fn read_line(&mut self, input: &str) {
+    pub fn read_line(&mut self, input: &str, line: usize) -> Result<()> {
         assert!(!self.complete);
 
         if input.trim().is_empty() {
@@ -117,24 +121,39 @@ impl BingoCardBuilder {
             if self.accum.len() == 5 {
                 self.cards.push(self.accum.clone());
                 self.accum = Vec::new();
-            } else {
-                assert_eq!(0, self.accum.len());
+            } else if !self.accum.is_empty() {
+                return Err(Error::parse(
+                    line,
+                    0,
+                    format!("card ended after {} rows", self.accum.len()),
+                ));
             }
         } else {
             // we should add to an existing card
-            self.accum.push(
-                input
-                    .trim()
-                    .replace("  ", " 0")
-                    .split(' ')
-                    .map(|s| s.parse::<u8>())
-                    .filter_map(Result::ok)
-                    .collect(),
-            );
+            let row = input
+                .split_whitespace()
+                .map(|s| {
+                    s.parse::<u8>().map_err(|_| {
+                        Error::parse(line, input.find(s).unwrap_or(0) + 1, "not a number")
+                    })
+                })
+                .collect::<Result<Vec<u8>>>()?;
+            if row.len() != 5 {
+                return Err(Error::parse(
+                    line,
+                    0,
+                    format!("expected 5 numbers, found {}", row.len()),
+                ));
+            }
+            if self.accum.len() == 5 {
+                return Err(Error::parse(line, 0, "card has more than 5 rows"));
+            }
+            self.accum.push(row);
         }
+        Ok(())
     }
 
-    pub fn build(&mut self, into: &mut Vec<BingoCard>) {
+    pub fn build(&mut self, into: &mut Vec<BingoCard>) -> Result<()> {
         self.complete = true;
         for card in self.cards.iter() {
             // why do i need to copy it ? this seems wierd
@@ -145,70 +164,142 @@ impl BingoCardBuilder {
             // push the last card
             let card_data = self.accum.clone();
             into.push(BingoCard::new(card_data));
-        } else {
+        } else if !self.accum.is_empty() {
             // otherwise, we should have no lines
-            // assert_eq!(0, self.accum.len());
-            unreachable!();
+            return Err(Error::invalid(format!(
+                "last card has {} rows",
+                self.accum.len()
+            )));
         }
+        if into.is_empty() {
+            return Err(Error::invalid("no bingo cards"));
+        }
+        Ok(())
     }
 }
 
-pub fn read_blocks_from_file(file_path: &str) -> CalculationInput {
-    let mut iter = super::util::read_strings_from_file(file_path);
+pub fn read_blocks_from_file(file_path: &str) -> Result<CalculationInput> {
+    read_blocks(super::util::read_strings_from_file(file_path)?)
+}
 
+pub fn read_blocks(mut iter: impl Iterator<Item = String>) -> Result<CalculationInput> {
     // the first line will be the inputs
     let draw: Vec<u8> = if let Some(first) = iter.next() {
         first
             .trim()
             .split(',')
-            .into_iter()
-            .map(|v| match v.parse::<u8>() {
-                Ok(n) => n,
-                Err(_) => unreachable!(),
+            .map(|v| {
+                v.parse::<u8>()
+                    .map_err(|_| Error::parse(1, first.find(v).unwrap_or(0) + 1, "not a number"))
             })
-            .collect()
+            .collect::<Result<Vec<u8>>>()?
     } else {
-        unreachable!();
+        return Err(Error::parse(1, 0, "no draw numbers"));
     };
 
     // this function needs to own the cards, can't be given from builder?
     let mut cards = Vec::new();
 
-    iter.fold(BingoCardBuilder::new(), |mut acc, x| {
-        acc.read_line(x.as_str());
-        acc
-    })
-    .build(&mut cards);
+    let mut builder = BingoCardBuilder::new();
+    for (i, x) in iter.enumerate() {
+        // the draw was line 1
+        builder.read_line(x.as_str(), i + 2)?;
+    }
+    builder.build(&mut cards)?;
 
     // for num in draw.iter() {
     //     println!("Checking {}", num);
     // }
-    (draw, cards)
+    Ok((draw, cards))
 }
 
-pub fn part1(_input: CalculationInput) -> DayResult {
+/// Every problem with the draw and the cards, each of which is 5x5
+pub fn check(raw: &str) -> Vec<Error> {
+    let mut problems = Vec::new();
+    let mut lines = raw.lines().enumerate().map(|(i, line)| (i + 1, line));
+
+    match lines.next() {
+        Some((_, draw)) if !draw.trim().is_empty() => {
+            let mut column = 1;
+            for v in draw.trim().split(',') {
+                if v.parse::<u8>().is_err() {
+                    problems.push(Error::parse(1, column, format!("not a number: {}", v)));
+                }
+                column += v.len() + 1;
+            }
+        }
+        _ => problems.push(Error::parse(1, 0, "no draw numbers")),
+    }
+
+    // each card is a run of lines between blank ones
+    let mut cards: Vec<Vec<(usize, &str)>> = Vec::new();
+    let mut card = Vec::new();
+    for (line, row) in lines {
+        if !row.trim().is_empty() {
+            card.push((line, row));
+        } else if !card.is_empty() {
+            cards.push(std::mem::take(&mut card));
+        }
+    }
+    if !card.is_empty() {
+        cards.push(card);
+    }
+    if cards.is_empty() {
+        problems.push(Error::parse(0, 0, "no bingo cards"));
+    }
+
+    for card in cards {
+        if card.len() != 5 {
+            problems.push(Error::parse(
+                card[0].0,
+                0,
+                format!("card has {} rows, expected 5", card.len()),
+            ));
+        }
+        for (line, row) in card {
+            let numbers: Vec<&str> = row.split_whitespace().collect();
+            if numbers.len() != 5 {
+                problems.push(Error::parse(
+                    line,
+                    0,
+                    format!("expected 5 numbers, found {}", numbers.len()),
+                ));
+            }
+            for v in numbers.iter().filter(|v| v.parse::<u8>().is_err()) {
+                problems.push(Error::parse(
+                    line,
+                    row.find(v).unwrap_or(0) + 1,
+                    format!("not a number: {}", v),
+                ));
+            }
+        }
+    }
+    problems
+}
+
+pub fn part1(_input: CalculationInput) -> Result<DayResult> {
     let (draw, mut cards) = _input;
     for number in draw.iter() {
-        println!("Checking {}", number);
+        crate::trace!("checking {}", number);
         for card in cards.iter_mut() {
             let winner = card.mark_number(number);
             if winner {
-                println!("we have a winner! {}", card.winner_to_str());
-                return card.score() * (*number as u64);
+                crate::debug!("we have a winner! {}", card.winner_to_str());
+                return Ok(card.score() * (*number as u64));
             }
         }
     }
 
-    unreachable!();
+    Err(Error::invalid("no card won"))
 }
 
-pub fn part2(_input: CalculationInput) -> DayResult {
+pub fn part2(_input: CalculationInput) -> Result<DayResult> {
     let (draw, mut cards) = _input;
 
     let mut has_won: usize = cards.len();
 
     for number in draw.iter() {
-        println!("Checking {}", number);
+        crate::trace!("checking {}", number);
         // check cards that haven't already won
         for card in cards.iter_mut().filter(|c| !c.is_winner()) {
             // and are now winners
@@ -217,47 +308,134 @@ pub fn part2(_input: CalculationInput) -> DayResult {
                 has_won -= 1;
                 // the card that was the last to win
                 if has_won == 0 {
-                    println!("last to win should be ! {}", card.winner_to_str());
-                    return card.score() * (*number as u64);
+                    crate::debug!("last to win should be ! {}", card.winner_to_str());
+                    return Ok(card.score() * (*number as u64));
                 }
             }
         }
     }
-    unreachable!();
+    Err(Error::invalid(format!("{} cards never won", has_won)))
+}
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u8 = 4;
+
+    type Input = CalculationInput;
+    type Answer1 = DayResult;
+    type Answer2 = DayResult;
+
+    fn parse(raw: &str) -> Result<Self::Input> {
+        read_blocks(raw.lines().map(String::from))
+    }
+
+    fn validate(raw: &str) -> Vec<Error> {
+        check(raw)
+    }
+
+    fn part1(input: Self::Input) -> Result<Self::Answer1> {
+        part1(input)
+    }
+
+    fn part2(input: Self::Input) -> Result<Self::Answer2> {
+        part2(input)
+    }
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::answers::verify;
+    use crate::solution::Part;
 
     fn test_data() -> CalculationInput {
-        super::read_blocks_from_file("data/day4_test.txt")
+        super::read_blocks_from_file("data/day4_test.txt").expect("test data")
     }
 
     fn puzzle_input() -> CalculationInput {
-        super::read_blocks_from_file("data/day4.txt")
+        super::read_blocks_from_file("data/day4.txt").expect("puzzle input")
     }
 
     #[test]
     fn test_part1() {
-        let result = part1(test_data());
+        let result = part1(test_data()).unwrap();
 
         assert_eq!(4512, result);
 
-        let result = part1(puzzle_input());
+        let result = part1(puzzle_input()).unwrap();
 
-        println!("day 4 part 1: {}", result);
+        verify(4, Part::One, "day4.txt", result);
     }
 
     #[test]
     fn test_part2() {
-        let result = part2(test_data());
+        let result = part2(test_data()).unwrap();
 
         assert_eq!(1924, result);
 
-        let result = part2(puzzle_input());
+        let result = part2(puzzle_input()).unwrap();
+
+        verify(4, Part::Two, "day4.txt", result);
+    }
 
-        println!("day 4 part 2: {}", result);
+    #[test]
+    fn test_bad_input() {
+        let lines = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<String>>();
+
+        assert_matches!(
+            read_blocks(lines(&[]).into_iter()),
+            Err(Error::Parse { line: 1, .. })
+        );
+        assert_matches!(
+            read_blocks(lines(&["1,2,x"]).into_iter()),
+            Err(Error::Parse {
+                line: 1,
+                column: 5,
+                ..
+            })
+        );
+        assert_matches!(
+            read_blocks(lines(&["1,2", "", "1 2 3 4 5", "", "1 2 3 4 5"]).into_iter()),
+            Err(Error::Parse { line: 4, .. })
+        );
+        assert_matches!(
+            read_blocks(lines(&["1,2", "", "1 2 3 4"]).into_iter()),
+            Err(Error::Parse { line: 3, .. })
+        );
+        assert_matches!(
+            read_blocks(lines(&["1,2", ""]).into_iter()),
+            Err(Error::InvalidState(_))
+        );
+
+        let card = lines(&[
+            "1,2",
+            "",
+            "1 2 3 4 5",
+            "6 7 8 9 10",
+            "11 12 13 14 15",
+            "16 17 18 19 20",
+            "21 22 23 24 25",
+        ]);
+        assert_matches!(
+            part1(read_blocks(card.into_iter()).unwrap()),
+            Err(Error::InvalidState(_))
+        );
+    }
+
+    #[test]
+    fn test_check() {
+        let raw = "1,2,x\n\n1 2 3 4 5\n6 7 8 9\n\n1 2 3 4 5\n";
+        let problems: Vec<String> = check(raw).iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            vec![
+                "line 1, column 5: not a number: x",
+                "line 3: card has 2 rows, expected 5",
+                "line 4: expected 5 numbers, found 4",
+                "line 6: card has 1 rows, expected 5",
+            ],
+            problems
+        );
     }
 }
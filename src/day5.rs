@@ -1,7 +1,10 @@
 type CalculationInput = (i32, i32, i32, i32);
 type DayResult = i32;
-type ParseResult = Result<CalculationInput, String>;
+type ParseResult = Result<CalculationInput>;
 
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+use crate::stream::Streaming;
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::collections::HashMap;
@@ -20,27 +23,28 @@ use std::collections::HashMap;
 ///     Err(_) => unreachable!(),
 /// };
 /// match day5::parse_line("nothing".to_string()) {
-///     Err(msg) => assert_eq!("error parsing: nothing", msg),
+///     Err(e) => assert_eq!("column 1: error parsing: nothing", e.to_string()),
 ///     Ok(_) => unreachable!(),
 /// };
 /// ```
 pub fn parse_line(input: String) -> ParseResult {
     lazy_static! {
-        static ref RE: Regex = Regex::new(r"^(\d+),(\d+) .. (\d+),(\d+)$").unwrap();
+        static ref RE: Regex = Regex::new(r"^(\d+),(\d+) -> (\d+),(\d+)$").unwrap();
     }
 
     if let Some(cap) = RE.captures(input.as_str()) {
         // capture 0 is the full line
         assert_eq!(cap.len(), 5);
         // capture 1-4 are the matches
-        Ok((
-            cap.get(1).unwrap().as_str().parse::<i32>().unwrap(),
-            cap.get(2).unwrap().as_str().parse::<i32>().unwrap(),
-            cap.get(3).unwrap().as_str().parse::<i32>().unwrap(),
-            cap.get(4).unwrap().as_str().parse::<i32>().unwrap(),
-        ))
+        let number = |i: usize| {
+            let m = cap.get(i).unwrap();
+            m.as_str()
+                .parse::<i32>()
+                .map_err(|_| Error::parse_line(m.start() + 1, "number too large"))
+        };
+        Ok((number(1)?, number(2)?, number(3)?, number(4)?))
     } else {
-        Err(format!("error parsing: {}", input))
+        Err(Error::parse_line(1, format!("error parsing: {}", input)))
     }
 }
 
@@ -53,27 +57,31 @@ pub fn part2(_input: impl Iterator<Item = CalculationInput>) -> DayResult {
 }
 
 pub fn calculate(_input: impl Iterator<Item = CalculationInput>, count_diag: bool) -> DayResult {
-    let mut counts: HashMap<(i32, i32), bool> = HashMap::new();
-
-    // this cannot be a fn, as it needs access to the local scope.
-    // closures have access to the local scope.
-
-    // not sure why the function itself is mutable, but it doesn't
-    // run without it being so.
-    let mut add_counts = |x: i32, y: i32| {
-        let key = (x, y);
-        match counts.get(&key) {
-            Some(v) if !*v => {
-                counts.insert(key, true);
-            }
-            Some(_) => (),
-            None => {
-                counts.insert(key, false);
-            }
+    let mut overlaps = Overlaps::new(count_diag);
+    for line in _input {
+        overlaps.add(line);
+    }
+    overlaps.answer()
+}
+
+/// The points covered by more than one of the lines so far
+#[derive(Clone, Debug, Default)]
+pub struct Overlaps {
+    count_diag: bool,
+    /// every point covered, and whether more than once
+    counts: HashMap<(i32, i32), bool>,
+    overlaps: DayResult,
+}
+
+impl Overlaps {
+    pub fn new(count_diag: bool) -> Self {
+        Overlaps {
+            count_diag,
+            ..Overlaps::default()
         }
-    };
+    }
 
-    for (mut fx, mut fy, tx, ty) in _input {
+    pub fn add(&mut self, (mut fx, mut fy, tx, ty): CalculationInput) {
         // calculate the detla between f->t
         let xd: i32 = match fx {
             x if x > tx => -1,
@@ -87,35 +95,87 @@ pub fn calculate(_input: impl Iterator<Item = CalculationInput>, count_diag: boo
             _ => 0,
         };
 
-        if !(count_diag || xd == 0 || yd == 0) {
+        if !(self.count_diag || xd == 0 || yd == 0) {
             // first pass, we only care about horizontal/vertical
-            continue;
+            return;
         }
 
         // add the initial point
-        add_counts(fx, fy);
+        self.add_point(fx, fy);
         // then iterate until we get to the final point
         while !(fx == tx && fy == ty) {
             fx += xd;
             fy += yd;
-            add_counts(fx, fy);
+            self.add_point(fx, fy);
+        }
+    }
+
+    fn add_point(&mut self, x: i32, y: i32) {
+        match self.counts.get_mut(&(x, y)) {
+            Some(seen_twice) if !*seen_twice => {
+                *seen_twice = true;
+                self.overlaps += 1;
+            }
+            Some(_) => (),
+            None => {
+                self.counts.insert((x, y), false);
+            }
         }
     }
-    // how many true values (more than one match) in the map
-    counts.values().fold(0, |a, v| a + if *v { 1 } else { 0 })
+}
+
+impl Streaming for Overlaps {
+    type Answer = DayResult;
+
+    fn push(&mut self, line: &str) -> Result<()> {
+        self.add(parse_line(line.to_string())?);
+        Ok(())
+    }
+
+    fn answer(&self) -> DayResult {
+        self.overlaps
+    }
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: u8 = 5;
+
+    type Input = Vec<CalculationInput>;
+    type Answer1 = DayResult;
+    type Answer2 = DayResult;
+
+    fn parse(raw: &str) -> Result<Self::Input> {
+        super::util::parse_lines(raw, parse_line)
+    }
+
+    fn validate(raw: &str) -> Vec<Error> {
+        super::util::check_lines(raw, parse_line)
+    }
+
+    fn part1(input: Self::Input) -> Result<Self::Answer1> {
+        Ok(part1(input.into_iter()))
+    }
+
+    fn part2(input: Self::Input) -> Result<Self::Answer2> {
+        Ok(part2(input.into_iter()))
+    }
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::answers::verify;
+    use crate::solution::Part;
 
     fn test_data() -> impl Iterator<Item = CalculationInput> {
-        super::super::util::parse_file("data/day5_test.txt", parse_line)
+        super::super::util::parse_file("data/day5_test.txt", parse_line).expect("test data")
     }
 
     fn puzzle_input() -> impl Iterator<Item = CalculationInput> {
-        super::super::util::parse_file("data/day5.txt", parse_line)
+        super::super::util::parse_file("data/day5.txt", parse_line).expect("puzzle input")
     }
 
     #[test]
@@ -126,7 +186,7 @@ mod tests {
 
         let result = part1(puzzle_input());
 
-        println!("day 5 part 1: {}", result);
+        verify(5, Part::One, "day5.txt", result);
     }
 
     #[test]
@@ -137,14 +197,18 @@ mod tests {
 
         let result = part2(puzzle_input());
 
-        println!("day 5 part 2: {}", result);
+        verify(5, Part::Two, "day5.txt", result);
     }
 
     #[test]
     fn test_cov() {
         match parse_line("nothing".to_string()) {
-            Err(msg) => assert_eq!("error parsing: nothing", msg),
-            Ok(_) => unreachable!(),
+            Err(Error::Parse { message, .. }) => assert_eq!("error parsing: nothing", message),
+            _ => unreachable!(),
         };
+        assert_matches!(
+            parse_line("0,9 -> 99999999999,9".to_string()),
+            Err(Error::Parse { column: 8, .. })
+        );
     }
 }
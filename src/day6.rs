@@ -1,37 +1,37 @@
+use crate::error::{Error, Result};
+use crate::solution::Solution;
 use std::collections::HashMap;
-type ParseResult = Result<CalculationInput, String>;
+type ParseResult = Result<CalculationInput>;
 type CalculationInput = HashMap<u8, u64>;
 type DayResult = u64;
 
 pub fn parse_line(input: String) -> ParseResult {
-    Result::Ok(
-        input
-            .trim()
-            .split(',')
-            .map(|v| v.parse::<u8>().expect("should be int"))
-            .fold(HashMap::new(), |mut acc, i| {
-                match acc.get(&i) {
-                    Some(v) => {
-                        let inc = v + 1;
-                        acc.insert(i, inc)
-                    }
-                    None => acc.insert(i, 1),
-                };
-                acc
-            }),
-    )
+    let mut column = 1;
+    let mut values = Vec::new();
+    for v in input.trim().split(',') {
+        values.push(
+            v.parse::<u8>()
+                .map_err(|_| Error::parse_line(column, format!("not a number: {}", v)))?,
+        );
+        column += v.len() + 1;
+    }
+    Result::Ok(values.into_iter().fold(HashMap::new(), |mut acc, i| {
+        match acc.get(&i) {
+            Some(v) => {
+                let inc = v + 1;
+                acc.insert(i, inc)
+            }
+            None => acc.insert(i, 1),
+        };
+        acc
+    }))
 }
 
-fn process(input: CalculationInput, days: u32) -> DayResult {
+pub fn process(input: CalculationInput, days: u32) -> DayResult {
     let mut fish = input;
 
     for _day in 0..days {
-        let new_fish = if let Some(vv) = fish.remove(&0) {
-            // we have a value
-            vv
-        } else {
-            0
-        };
+        let new_fish = fish.remove(&0).unwrap_or_default();
         for (age, age_minus_one) in (1..9).map(|v| (v as u8, (v - 1) as u8)) {
             if let Some(fish_count) = fish.remove(&age) {
                 fish.insert(age_minus_one, fish_count);
@@ -69,19 +69,52 @@ pub fn part2(input: CalculationInput) -> DayResult {
     process(input, 256)
 }
 
+pub struct Day6;
+
+impl Solution for Day6 {
+    const DAY: u8 = 6;
+
+    type Input = CalculationInput;
+    type Answer1 = DayResult;
+    type Answer2 = DayResult;
+
+    fn parse(raw: &str) -> Result<Self::Input> {
+        super::util::parse_lines(raw, parse_line)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::parse(1, 0, "there should be one line"))
+    }
+
+    fn validate(raw: &str) -> Vec<Error> {
+        super::util::check_single_line(raw, parse_line)
+    }
+
+    fn part1(input: Self::Input) -> Result<Self::Answer1> {
+        Ok(part1(input))
+    }
+
+    fn part2(input: Self::Input) -> Result<Self::Answer2> {
+        Ok(part2(input))
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::answers::verify;
+    use crate::solution::Part;
 
     fn test_data() -> CalculationInput {
         super::super::util::parse_file("data/day6_test.txt", parse_line)
+            .expect("test data")
             .next()
             .expect("there should be one line")
     }
 
     fn puzzle_input() -> CalculationInput {
         super::super::util::parse_file("data/day6.txt", parse_line)
+            .expect("puzzle input")
             .next()
             .expect("there should be one line")
     }
@@ -94,7 +127,7 @@ mod tests {
 
         let result = part1(puzzle_input());
 
-        println!("day 6 part 1: {}", result);
+        verify(6, Part::One, "day6.txt", result);
     }
 
     #[test]
@@ -105,6 +138,15 @@ mod tests {
 
         let result = part2(puzzle_input());
 
-        println!("day 6 part 2: {}", result);
+        verify(6, Part::Two, "day6.txt", result);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert_matches!(
+            parse_line("3,4,x,1".to_string()),
+            Err(Error::Parse { column: 5, .. })
+        );
+        assert_matches!(Day6::parse(""), Err(Error::Parse { line: 1, .. }));
     }
 }
@@ -1,43 +1,41 @@
-use std::collections::HashMap;
-type ParseResult = Result<CalculationInput, String>;
+use crate::error::{Error, Result};
+use crate::report::Report;
+use crate::solution::Solution;
+use std::collections::{HashMap, HashSet};
+type ParseResult = Result<CalculationInput>;
 type CalculationInput = HashMap<u64, u64>;
 type DayResult = (u64, u64);
 
 pub fn parse_line(input: String) -> ParseResult {
-    Result::Ok(
-        input
-            .trim()
-            .split(',')
-            .map(|v| v.parse::<u64>().expect("should be int"))
-            .fold(HashMap::new(), |mut acc, i| {
-                if let Some(v) = acc.remove(&i) {
-                    acc.insert(i, v + 1);
-                } else {
-                    acc.insert(i, 1);
-                }
-                acc
-            }),
-    )
+    let mut column = 1;
+    let mut values = Vec::new();
+    for v in input.trim().split(',') {
+        values.push(
+            v.parse::<u64>()
+                .map_err(|_| Error::parse_line(column, format!("not a number: {}", v)))?,
+        );
+        column += v.len() + 1;
+    }
+    Result::Ok(values.into_iter().fold(HashMap::new(), |mut acc, i| {
+        if let Some(v) = acc.remove(&i) {
+            acc.insert(i, v + 1);
+        } else {
+            acc.insert(i, 1);
+        }
+        acc
+    }))
 }
 
 pub fn calculate_fuel_constant(candidate: &u64, input: &CalculationInput) -> u64 {
     input.iter().fold(0, |acc: u64, (k, v)| {
-        let fuel = if *candidate > *k {
-            (*candidate - *k) * v
-        } else {
-            (*k - *candidate) * v
-        };
+        let fuel = candidate.abs_diff(*k) * v;
         acc + fuel
     })
 }
 
 pub fn calculate_fuel_increasing(candidate: &u64, input: &CalculationInput) -> u64 {
     input.iter().fold(0, |acc: u64, (k, v)| {
-        let mut steps = if *candidate > *k {
-            *candidate - *k
-        } else {
-            *k - *candidate
-        };
+        let mut steps = candidate.abs_diff(*k);
 
         let mut fuel = 0;
         let mut cost = 1;
@@ -50,27 +48,30 @@ pub fn calculate_fuel_increasing(candidate: &u64, input: &CalculationInput) -> u
     })
 }
 
-pub fn part1(input: CalculationInput) -> DayResult {
+pub fn part1(input: CalculationInput) -> Result<DayResult> {
     calculate(input, calculate_fuel_constant)
 }
 
-pub fn part2(input: CalculationInput) -> DayResult {
+pub fn part2(input: CalculationInput) -> Result<DayResult> {
     calculate(input, calculate_fuel_increasing)
 }
 
 pub fn calculate(
     input: CalculationInput,
     calculate_fuel: impl Fn(&u64, &CalculationInput) -> u64,
-) -> DayResult {
+) -> Result<DayResult> {
     let (sum, entries) = input.iter().fold((0, 0), |acc: (u64, u64), (k, v)| {
         let (mut sum, mut entries) = acc;
-        sum += *k as u64 * *v;
+        sum += *k * *v;
         entries += *v;
         (sum, entries)
     });
     // println!("sum={}, entries={}", sum, entries);
+    if entries == 0 {
+        return Err(Error::invalid("no crab positions"));
+    }
 
-    let mut candidate = (sum / entries) as u64;
+    let mut candidate = sum / entries;
     // start from the candidate
     let mut candidate_score = calculate_fuel(&candidate, &input);
 
@@ -78,17 +79,16 @@ pub fn calculate(
 
     let mut found_alternate = true;
 
-    let mut calculated = HashMap::new();
+    let mut calculated = HashSet::new();
 
     while found_alternate {
         found_alternate = false;
 
-        for alternate in (if candidate > 3 { candidate - 3 } else { 0 })..(candidate + 3) {
-            if calculated.get(&alternate).is_none() {
+        for alternate in candidate.saturating_sub(3)..(candidate + 3) {
+            if calculated.insert(alternate) {
                 // println!("checking {}", alternate);
                 // we haven't already checked this number
                 let alternate_score = calculate_fuel(&alternate, &input);
-                calculated.insert(alternate, alternate_score);
                 if alternate_score < candidate_score {
                     candidate = alternate;
                     candidate_score = alternate_score;
@@ -99,47 +99,103 @@ pub fn calculate(
         }
     }
 
-    (candidate, candidate_score)
+    Ok((candidate, candidate_score))
+}
+
+fn report((position, fuel): DayResult) -> Report {
+    Report::new(fuel)
+        .with("position", position)
+        .with("fuel", fuel)
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: u8 = 7;
+
+    type Input = CalculationInput;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(raw: &str) -> Result<Self::Input> {
+        super::util::parse_lines(raw, parse_line)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::parse(1, 0, "there should be one line"))
+    }
+
+    fn validate(raw: &str) -> Vec<Error> {
+        super::util::check_single_line(raw, parse_line)
+    }
+
+    fn part1(input: Self::Input) -> Result<Self::Answer1> {
+        part1(input).map(|r| r.1)
+    }
+
+    fn part2(input: Self::Input) -> Result<Self::Answer2> {
+        part2(input).map(|r| r.1)
+    }
+
+    fn report1(input: Self::Input) -> Result<Report> {
+        part1(input).map(report)
+    }
+
+    fn report2(input: Self::Input) -> Result<Report> {
+        part2(input).map(report)
+    }
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::answers::verify;
+    use crate::solution::Part;
 
     fn test_data() -> CalculationInput {
         super::super::util::parse_file("data/day7_test.txt", parse_line)
+            .expect("test data")
             .next()
             .expect("there should be one line")
     }
 
     fn puzzle_input() -> CalculationInput {
         super::super::util::parse_file("data/day7.txt", parse_line)
+            .expect("puzzle input")
             .next()
             .expect("there should be one line")
     }
 
     #[test]
     fn test_part1() {
-        let (result, fuel) = part1(test_data());
+        let (result, fuel) = part1(test_data()).unwrap();
 
         assert_eq!(2, result);
         assert_eq!(37, fuel);
 
-        let (result, fuel) = part1(puzzle_input());
+        let (_, fuel) = part1(puzzle_input()).unwrap();
 
-        println!("day 7 part 1: result={}, fuel={}", result, fuel);
+        verify(7, Part::One, "day7.txt", fuel);
     }
 
     #[test]
     fn test_part2() {
-        let (result, fuel) = part2(test_data());
+        let (result, fuel) = part2(test_data()).unwrap();
 
         assert_eq!(5, result);
         assert_eq!(168, fuel);
 
-        let (result, fuel) = part2(puzzle_input());
+        let (_, fuel) = part2(puzzle_input()).unwrap();
 
-        println!("day 7 part 2: result={}, fuel={}", result, fuel);
+        verify(7, Part::Two, "day7.txt", fuel);
+    }
+
+    #[test]
+    fn test_bad_input() {
+        assert_matches!(
+            parse_line("16,1,,2".to_string()),
+            Err(Error::Parse { column: 6, .. })
+        );
+        assert_matches!(part1(HashMap::new()), Err(Error::InvalidState(_)));
     }
 }
@@ -1,6 +1,8 @@
+use crate::error::{Error, Result};
+use crate::solution::Solution;
 use itertools::Itertools;
 use std::collections::HashMap;
-type ParseResult = Result<CalculationInput, String>;
+type ParseResult = Result<CalculationInput>;
 type CalculationInput = (Vec<String>, Vec<String>);
 type DayResult = u64;
 
@@ -19,11 +21,16 @@ pub fn parse_line(input: String) -> ParseResult {
             .collect()
     });
 
-    let mut signal: Vec<String> = iter.next().expect("signal patterns");
-    let output: Vec<String> = iter.next().expect("output value");
+    let mut signal: Vec<String> = iter.next().unwrap_or_default();
+    let output: Vec<String> = iter
+        .next()
+        .ok_or_else(|| Error::parse_line(0, "expected signal patterns | output value"))?;
 
     if iter.next().is_some() {
-        Err("more records in the iterator than we expected".to_string())
+        Err(Error::parse_line(
+            0,
+            "more records in the iterator than we expected",
+        ))
     } else {
         // smallest to largest by length
         signal.sort_by(|a, b| a.len().partial_cmp(&b.len()).unwrap());
@@ -31,6 +38,27 @@ pub fn parse_line(input: String) -> ParseResult {
     }
 }
 
+/// A line has ten signal patterns and four output digits, using segments a to g
+pub fn check_line(line: String) -> Result<()> {
+    if let Some(i) = line.find(|c: char| !matches!(c, 'a'..='g' | ' ' | '|')) {
+        return Err(Error::parse_line(i + 1, "segments are a to g"));
+    }
+    let (signal, output) = parse_line(line)?;
+    if signal.len() != 10 {
+        return Err(Error::parse_line(
+            0,
+            format!("expected 10 signal patterns, found {}", signal.len()),
+        ));
+    }
+    if output.len() != 4 {
+        return Err(Error::parse_line(
+            0,
+            format!("expected 4 output values, found {}", output.len()),
+        ));
+    }
+    Ok(())
+}
+
 pub fn part1(input: impl Iterator<Item = CalculationInput>) -> DayResult {
     // part one is a simple filter for those which have detectable digits
 
@@ -70,10 +98,18 @@ fn all_in(str: &str, str2: &str) -> bool {
     str.chars().all(|c| str2.contains(c))
 }
 
-pub fn calculate(input: CalculationInput) -> DayResult {
+pub fn calculate(input: CalculationInput) -> Result<DayResult> {
     // signal_patterns are sorted in length order ..
     let (signal_patterns, output_values) = input;
 
+    let lengths: Vec<usize> = signal_patterns.iter().map(|p| p.len()).collect();
+    if lengths != [2, 3, 4, 5, 5, 5, 6, 6, 6, 7] {
+        return Err(Error::invalid(format!(
+            "signal patterns are not one of each digit: {}",
+            signal_patterns.join(" ")
+        )));
+    }
+
     let mut entries: HashMap<String, u64> = HashMap::new();
 
     // so the 1st will be 1 with len=2
@@ -134,27 +170,57 @@ pub fn calculate(input: CalculationInput) -> DayResult {
         }
     }
     // now we know what all the values are, we can map to the result
-    output_values
-        .iter()
-        .map(|v| entries.get(v).unwrap())
-        .fold(0, |acc, vv| (acc * 10) + *vv)
+    output_values.iter().try_fold(0, |acc, v| {
+        entries
+            .get(v)
+            .map(|vv| (acc * 10) + *vv)
+            .ok_or_else(|| Error::invalid(format!("unknown output pattern: {}", v)))
+    })
 }
 
-pub fn part2(input: impl Iterator<Item = CalculationInput>) -> DayResult {
+pub fn part2(input: impl Iterator<Item = CalculationInput>) -> Result<DayResult> {
     input.map(calculate).sum()
 }
 
+pub struct Day8;
+
+impl Solution for Day8 {
+    const DAY: u8 = 8;
+
+    type Input = Vec<CalculationInput>;
+    type Answer1 = DayResult;
+    type Answer2 = DayResult;
+
+    fn parse(raw: &str) -> Result<Self::Input> {
+        super::util::parse_lines(raw, parse_line)
+    }
+
+    fn validate(raw: &str) -> Vec<Error> {
+        super::util::check_lines(raw, check_line)
+    }
+
+    fn part1(input: Self::Input) -> Result<Self::Answer1> {
+        Ok(part1(input.into_iter()))
+    }
+
+    fn part2(input: Self::Input) -> Result<Self::Answer2> {
+        part2(input.into_iter())
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::answers::verify;
+    use crate::solution::Part;
 
     fn test_data() -> impl Iterator<Item = CalculationInput> {
-        super::super::util::parse_file("data/day8_test.txt", parse_line)
+        super::super::util::parse_file("data/day8_test.txt", parse_line).expect("test data")
     }
 
     fn puzzle_input() -> impl Iterator<Item = CalculationInput> {
-        super::super::util::parse_file("data/day8.txt", parse_line)
+        super::super::util::parse_file("data/day8.txt", parse_line).expect("puzzle input")
     }
 
     #[test]
@@ -165,7 +231,7 @@ mod tests {
 
         let input = parse_line(line).expect("good line");
 
-        let result = calculate(input);
+        let result = calculate(input).unwrap();
 
         assert_eq!(result, 5353);
     }
@@ -178,17 +244,48 @@ mod tests {
 
         let result = part1(puzzle_input());
 
-        println!("day 8 part 1: result={}", result);
+        verify(8, Part::One, "day8.txt", result);
     }
 
     #[test]
     fn test_part2() {
-        let result = part2(test_data());
+        let result = part2(test_data()).unwrap();
 
         assert_eq!(61229, result);
 
-        let result = part2(puzzle_input());
+        let result = part2(puzzle_input()).unwrap();
+
+        verify(8, Part::Two, "day8.txt", result);
+    }
+
+    #[test]
+    fn test_bad_line() {
+        assert_matches!(parse_line("ab cd".to_string()), Err(Error::Parse { .. }));
+        assert_matches!(
+            parse_line("ab | cd | ef".to_string()),
+            Err(Error::Parse { .. })
+        );
+
+        let input = parse_line("ab abc | ab".to_string()).expect("parses");
+        assert_matches!(calculate(input), Err(Error::InvalidState(_)));
 
-        println!("day 8 part 2: result={}", result);
+        let mut line = String::new();
+        line += "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab";
+        line += " | cdfeb fcadb cdfeb cdbafg";
+        let input = parse_line(line).expect("parses");
+        assert_matches!(calculate(input), Err(Error::InvalidState(_)));
+    }
+
+    #[test]
+    fn test_check() {
+        let raw = "ab cd | ef ga ab cd\nabcdefg | x\n";
+        let problems: Vec<String> = Day8::validate(raw).iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            vec![
+                "line 1: expected 10 signal patterns, found 2",
+                "line 2, column 11: segments are a to g",
+            ],
+            problems
+        );
     }
 }
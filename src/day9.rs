@@ -1,86 +1,50 @@
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+use crate::util::Grid;
 use std::collections::HashSet;
-type CalculationInput = Vec<u8>;
+type CalculationInput = Grid<u8>;
 type DayResult = u64;
 
-fn find_neighbors(all: &[Vec<u8>], i: usize, j: usize) -> Vec<(usize, usize, &u8)> {
-    let find = |x: usize, y: usize| {
-        if let Some(row) = all.get(x) {
-            if let Some(value) = row.get(y) {
-                return Some((x, y, value));
-            }
-        }
-        None
-    };
-    let mut bors = Vec::new();
-
-    // usize=0 - 1 will panic
-    if i > 0 {
-        if let Some(neighbor) = find(i - 1, j) {
-            bors.push(neighbor);
-        }
-    }
-    if j > 0 {
-        if let Some(neighbor) = find(i, j - 1) {
-            bors.push(neighbor);
-        }
-    }
-
-    if let Some(neighbor) = find(i + 1, j) {
-        bors.push(neighbor);
-    }
-    if let Some(neighbor) = find(i, j + 1) {
-        bors.push(neighbor);
-    }
-
-    bors
-}
-
-pub fn find_low_points(all: &[Vec<u8>]) -> Vec<(usize, usize, u8)> {
+pub fn find_low_points(all: &Grid<u8>) -> Vec<((usize, usize), u8)> {
     // find low points in the data
 
-    let mut results: Vec<(usize, usize, u8)> = Vec::new();
-
-    for (i, x_row) in all.iter().enumerate() {
-        'index: for (j, value) in x_row.iter().enumerate() {
-            // all arrays need to be the same size for this loop
-            // x must be >0 to use usize
+    let mut results: Vec<((usize, usize), u8)> = Vec::new();
 
-            if find_neighbors(all, i, j)
-                .iter()
-                .any(|(_, _, neighbor)| **neighbor <= *value)
-            {
-                continue 'index;
-            }
-            // if we got here, then i,j is a low point
-            println!("found low point with value: {} ({}x{})", *value, i, j);
-            // for (x, y, value) in find_neighbors(all, i, j) {
-            //     println!("n: {}x{} = {}", x, y, value);
-            // }
-            results.push((i, j, *value));
+    for (position, value) in all.iter() {
+        if all
+            .neighbors4(position)
+            .any(|neighbor| all[neighbor] <= *value)
+        {
+            continue;
         }
+        // if we got here, then this is a low point
+        crate::debug!(
+            "found low point with value: {} ({}x{})",
+            *value,
+            position.0,
+            position.1
+        );
+        results.push((position, *value));
     }
     results
 }
-pub fn part1(_input: impl Iterator<Item = CalculationInput>) -> DayResult {
-    let all: Vec<Vec<u8>> = _input.collect();
+pub fn part1(all: &CalculationInput) -> DayResult {
     let mut risk: DayResult = 0;
 
-    for (_, _, value) in find_low_points(&all) {
+    for (_, value) in find_low_points(all) {
         risk += value as u64 + 1
     }
     risk
 }
 
-pub fn part2(_input: impl Iterator<Item = CalculationInput>) -> DayResult {
-    let all: Vec<Vec<u8>> = _input.collect();
-
+pub fn part2(all: &CalculationInput) -> DayResult {
     let mut top_three: Vec<usize> = Vec::new();
 
-    for (x, y, value) in find_low_points(&all) {
+    for (position, _) in find_low_points(all) {
         // starting from this low point, find the size
         let mut seen = HashSet::new();
 
-        let mut next: Vec<(usize, usize, &u8)> = vec![(x, y, &value)];
+        let mut next: Vec<(usize, usize)> = vec![position];
 
         while !next.is_empty() {
             let to_check = next;
@@ -89,18 +53,14 @@ pub fn part2(_input: impl Iterator<Item = CalculationInput>) -> DayResult {
             // these items are by definition low
             // we are looking for items next to these
             // that haven't been seen
-            for (cx, cy, _) in to_check {
-                for n in find_neighbors(&all, cx, cy) {
-                    let (nx, ny, nv) = n;
-
-                    if *nv == 9 {
+            for current in to_check {
+                for n in all.neighbors4(current) {
+                    if all[n] == 9 {
                         continue;
                     }
-                    if seen.contains(&(nx, ny)) {
-                        continue;
+                    if seen.insert(n) {
+                        next.push(n);
                     }
-                    seen.insert((nx, ny));
-                    next.push(n);
                 }
             }
         }
@@ -124,37 +84,73 @@ pub fn part2(_input: impl Iterator<Item = CalculationInput>) -> DayResult {
     top_three.iter().fold(1, |a, v| a * *v) as u64
 }
 
+pub struct Day9;
+
+impl Solution for Day9 {
+    const DAY: u8 = 9;
+
+    type Input = CalculationInput;
+    type Answer1 = DayResult;
+    type Answer2 = DayResult;
+
+    fn parse(raw: &str) -> Result<Self::Input> {
+        Grid::parse_digits(raw)
+    }
+
+    fn validate(raw: &str) -> Vec<Error> {
+        super::util::check_grid(raw, |c| c.is_ascii_digit())
+    }
+
+    fn part1(input: Self::Input) -> Result<Self::Answer1> {
+        Ok(part1(&input))
+    }
+
+    fn part2(input: Self::Input) -> Result<Self::Answer2> {
+        Ok(part2(&input))
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::answers::verify;
+    use crate::solution::Part;
 
-    fn test_data() -> impl Iterator<Item = CalculationInput> {
-        super::super::util::read_int_list_from_file("data/day9_test.txt")
+    fn test_data() -> CalculationInput {
+        super::super::util::read_digit_grid_from_file("data/day9_test.txt").expect("test data")
     }
 
-    fn puzzle_input() -> impl Iterator<Item = CalculationInput> {
-        super::super::util::read_int_list_from_file("data/day9.txt")
+    fn puzzle_input() -> CalculationInput {
+        super::super::util::read_digit_grid_from_file("data/day9.txt").expect("puzzle input")
     }
 
     #[test]
     fn test_part1() {
-        let result = part1(test_data());
+        let result = part1(&test_data());
 
         assert_eq!(15, result);
 
-        let result = part1(puzzle_input());
-        println!("day 9 part 1: result={}", result);
+        let result = part1(&puzzle_input());
+        verify(9, Part::One, "day9.txt", result);
     }
 
     #[test]
     fn test_part2() {
-        let result = part2(test_data());
+        let result = part2(&test_data());
 
         assert_eq!(1134, result);
 
-        let result = part2(puzzle_input());
+        let result = part2(&puzzle_input());
+
+        verify(9, Part::Two, "day9.txt", result);
+    }
 
-        println!("day 9 part 2: result={}", result);
+    #[test]
+    fn test_bad_input() {
+        assert_matches!(
+            Day9::parse("2199\n398\n"),
+            Err(Error::Parse { line: 2, .. })
+        );
     }
 }
@@ -0,0 +1,342 @@
+//! Differential tests for the days that take a shortcut: each is compared
+//! with a plain reference solver over many generated inputs, and when the two
+//! disagree the input is shrunk to the smallest one that still shows it.
+
+use crate::generate::Rng;
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+/// Replace `case` with the first smaller candidate that still fails, until
+/// none of them do
+fn shrink<T>(mut case: T, smaller: impl Fn(&T) -> Vec<T>, fails: impl Fn(&T) -> bool) -> T {
+    'smaller: loop {
+        for candidate in smaller(&case) {
+            if fails(&candidate) {
+                case = candidate;
+                continue 'smaller;
+            }
+        }
+        return case;
+    }
+}
+
+/// Every list with one item left out, then every list with one item made
+/// smaller
+fn smaller_lists<T: Clone>(list: &[T], smaller: impl Fn(&T) -> Vec<T>) -> Vec<Vec<T>> {
+    let mut lists = Vec::new();
+    for i in 0..list.len() {
+        let mut shorter = list.to_vec();
+        shorter.remove(i);
+        lists.push(shorter);
+    }
+    for (i, item) in list.iter().enumerate() {
+        for replacement in smaller(item) {
+            let mut changed = list.to_vec();
+            changed[i] = replacement;
+            lists.push(changed);
+        }
+    }
+    lists
+}
+
+/// Zero and half way there, for shrinking numbers
+fn smaller_numbers(n: u64) -> Vec<u64> {
+    match n {
+        0 => vec![],
+        1 => vec![0],
+        n => vec![0, n / 2, n - 1],
+    }
+}
+
+/// Compare `fast` with `reference` on `cases` generated inputs, panicking
+/// with the smallest input they disagree on
+fn compare<T, A>(
+    name: &str,
+    cases: u64,
+    generate: impl Fn(&mut Rng) -> T,
+    smaller: impl Fn(&T) -> Vec<T>,
+    fast: impl Fn(&T) -> A,
+    reference: impl Fn(&T) -> A,
+) where
+    T: Debug,
+    A: Debug + PartialEq,
+{
+    let fails = |case: &T| fast(case) != reference(case);
+    for seed in 0..cases {
+        let case = generate(&mut Rng::new(seed));
+        if fails(&case) {
+            let case = shrink(case, &smaller, fails);
+            panic!(
+                "{} disagrees with the reference (seed {}) on {:?}: {:?} != {:?}",
+                name,
+                seed,
+                case,
+                fast(&case),
+                reference(&case)
+            );
+        }
+    }
+}
+
+#[test]
+fn test_shrink() {
+    // nothing can be left out or made smaller and still sum to over 10
+    let smallest = shrink(
+        vec![9, 4, 7, 1],
+        |list| smaller_lists(list, |n| smaller_numbers(*n)),
+        |list| list.iter().sum::<u64>() > 10,
+    );
+    assert_eq!(vec![4, 7], smallest);
+
+    assert_eq!(5, shrink(5, |n| smaller_numbers(*n), |n| *n > 4));
+}
+
+#[test]
+fn test_day7() {
+    // crab positions
+    let generate = |rng: &mut Rng| -> Vec<u64> {
+        let spread = rng.between(1, 30) as usize;
+        (0..rng.between(1, 12))
+            .map(|_| rng.below(spread) as u64)
+            .collect()
+    };
+    let smaller = |crabs: &Vec<u64>| {
+        smaller_lists(crabs, |n| smaller_numbers(*n))
+            .into_iter()
+            .filter(|c| !c.is_empty())
+            .collect()
+    };
+    let input = |crabs: &Vec<u64>| {
+        let line: Vec<String> = crabs.iter().map(|c| c.to_string()).collect();
+        crate::day7::parse_line(line.join(",")).unwrap()
+    };
+    let cheapest = |crabs: &Vec<u64>, cost: fn(u64) -> u64| {
+        (0..=*crabs.iter().max().unwrap())
+            .map(|to| crabs.iter().map(|c| cost(c.abs_diff(to))).sum::<u64>())
+            .min()
+            .unwrap()
+    };
+
+    for (part, fuel, cost) in [
+        (
+            "day7 part 1",
+            crate::day7::calculate_fuel_constant as fn(&u64, &HashMap<u64, u64>) -> u64,
+            (|d| d) as fn(u64) -> u64,
+        ),
+        ("day7 part 2", crate::day7::calculate_fuel_increasing, |d| {
+            d * (d + 1) / 2
+        }),
+    ] {
+        compare(
+            part,
+            500,
+            generate,
+            smaller,
+            |crabs| {
+                let input = input(crabs);
+                let (position, total) = crate::day7::calculate(input.clone(), fuel).unwrap();
+                // the fuel has to be what it costs to get to the position
+                assert_eq!(total, fuel(&position, &input));
+                total
+            },
+            |crabs| cheapest(crabs, cost),
+        );
+    }
+}
+
+#[test]
+fn test_day6() {
+    // timers and a number of days
+    let generate = |rng: &mut Rng| -> (Vec<u8>, u32) {
+        let fish = (0..rng.between(1, 8))
+            .map(|_| rng.between(0, 8) as u8)
+            .collect();
+        (fish, rng.between(0, 40) as u32)
+    };
+    let smaller = |(fish, days): &(Vec<u8>, u32)| {
+        let mut cases: Vec<(Vec<u8>, u32)> = smaller_lists(fish, |t| {
+            smaller_numbers(*t as u64)
+                .into_iter()
+                .map(|t| t as u8)
+                .collect()
+        })
+        .into_iter()
+        .filter(|f| !f.is_empty())
+        .map(|f| (f, *days))
+        .collect();
+        cases.extend(
+            smaller_numbers(*days as u64)
+                .into_iter()
+                .map(|d| (fish.clone(), d as u32)),
+        );
+        cases
+    };
+    let simulate = |(fish, days): &(Vec<u8>, u32)| {
+        let mut fish = fish.clone();
+        for _ in 0..*days {
+            let born = fish.iter().filter(|t| **t == 0).count();
+            for timer in fish.iter_mut() {
+                *timer = if *timer == 0 { 6 } else { *timer - 1 };
+            }
+            fish.extend(std::iter::repeat_n(8, born));
+        }
+        fish.len() as u64
+    };
+
+    compare(
+        "day6 process",
+        500,
+        generate,
+        smaller,
+        |(fish, days)| {
+            let line: Vec<String> = fish.iter().map(|t| t.to_string()).collect();
+            crate::day6::process(crate::day6::parse_line(line.join(",")).unwrap(), *days)
+        },
+        simulate,
+    );
+}
+
+/// template, rules and steps
+type Polymer = (String, Vec<(String, char)>, u8);
+
+#[test]
+fn test_day14() {
+    let generate = |rng: &mut Rng| -> Polymer {
+        let elements: Vec<char> = ('A'..='D').take(rng.between(1, 4) as usize).collect();
+        let template = (0..rng.between(2, 8))
+            .map(|_| *rng.choose(&elements))
+            .collect();
+        let mut rules = Vec::new();
+        for a in elements.iter() {
+            for b in elements.iter() {
+                if rng.chance(80) {
+                    rules.push((format!("{}{}", a, b), *rng.choose(&elements)));
+                }
+            }
+        }
+        (template, rules, rng.between(0, 8) as u8)
+    };
+    let smaller = |(template, rules, steps): &Polymer| {
+        let mut cases: Vec<Polymer> = Vec::new();
+        for i in 0..template.len() {
+            if template.len() > 2 {
+                let mut shorter = template.clone();
+                shorter.remove(i);
+                cases.push((shorter, rules.clone(), *steps));
+            }
+        }
+        for fewer in smaller_lists(rules, |_| vec![]) {
+            cases.push((template.clone(), fewer, *steps));
+        }
+        for fewer in smaller_numbers(*steps as u64) {
+            cases.push((template.clone(), rules.clone(), fewer as u8));
+        }
+        cases
+    };
+    let grow = |(template, rules, steps): &Polymer| {
+        let rules: HashMap<&str, char> = rules.iter().map(|(p, c)| (p.as_str(), *c)).collect();
+        let mut polymer: Vec<char> = template.chars().collect();
+        for _ in 0..*steps {
+            let mut next = vec![polymer[0]];
+            for pair in polymer.windows(2) {
+                let key: String = pair.iter().collect();
+                if let Some(c) = rules.get(key.as_str()) {
+                    next.push(*c);
+                }
+                next.push(pair[1]);
+            }
+            polymer = next;
+        }
+        let mut counts: HashMap<char, u128> = HashMap::new();
+        for c in polymer {
+            *counts.entry(c).or_insert(0) += 1;
+        }
+        counts.values().max().unwrap() - counts.values().min().unwrap()
+    };
+
+    compare(
+        "day14 run",
+        500,
+        generate,
+        smaller,
+        |(template, rules, steps)| {
+            let lines = [template.clone(), String::new()]
+                .into_iter()
+                .chain(rules.iter().map(|(p, c)| format!("{} -> {}", p, c)));
+            crate::day14::run(lines, *steps).unwrap()
+        },
+        grow,
+    );
+}
+
+/// x1, x2, y1, y2
+type Target = (i32, i32, i32, i32);
+
+#[test]
+fn test_day17() {
+    let generate = |rng: &mut Rng| -> Target {
+        let x1 = rng.between(1, 40) as i32;
+        let y1 = -rng.between(1, 40) as i32;
+        (
+            x1,
+            x1 + rng.between(0, 15) as i32,
+            y1,
+            (y1 + rng.between(0, 15) as i32).min(-1),
+        )
+    };
+    let smaller = |(x1, x2, y1, y2): &Target| {
+        let mut cases = Vec::new();
+        if x2 > x1 {
+            cases.push((*x1, x2 - 1, *y1, *y2));
+        }
+        if *x1 > 1 {
+            cases.push((x1 - 1, x2 - 1, *y1, *y2));
+        }
+        if y2 > y1 {
+            cases.push((*x1, *x2, y1 + 1, *y2));
+        }
+        if *y2 < -1 {
+            cases.push((*x1, *x2, y1 + 1, y2 + 1));
+        }
+        cases
+    };
+    let sorted = |mut hits: Vec<((i32, i32), i32)>| {
+        hits.sort();
+        hits
+    };
+    // every velocity that can possibly get there, one step at a time
+    let search = |(x1, x2, y1, y2): &Target| {
+        let mut hits = Vec::new();
+        for vx in 0..=*x2 {
+            for vy in *y1..=-*y1 {
+                let (mut x, mut y, mut dx, mut dy, mut top) = (0, 0, vx, vy, 0);
+                while x <= *x2 && y >= *y1 {
+                    if x >= *x1 && y <= *y2 {
+                        hits.push(((vx, vy), top));
+                        break;
+                    }
+                    x += dx;
+                    y += dy;
+                    top = top.max(y);
+                    dx -= dx.signum();
+                    dy -= 1;
+                }
+            }
+        }
+        sorted(hits)
+    };
+
+    compare(
+        "day17 run",
+        100,
+        generate,
+        smaller,
+        |(x1, x2, y1, y2)| {
+            let raw = format!("target area: x={}..{}, y={}..{}", x1, x2, y1, y2);
+            sorted(crate::day17::run(
+                crate::day17::parse(raw.as_str()).unwrap(),
+            ))
+        },
+        search,
+    );
+}
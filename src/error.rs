@@ -0,0 +1,134 @@
+use std::fmt;
+use std::io;
+
+/// Everything that can go wrong loading or solving a puzzle
+#[derive(Debug)]
+pub enum Error {
+    /// the input could not be read at all
+    Io { context: String, source: io::Error },
+    /// the input was read, but does not match the puzzle format.
+    /// line and column are 1-based, 0 when unknown.
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// the input parsed, but the puzzle has no answer for it
+    InvalidState(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    pub fn io(context: impl Into<String>, source: io::Error) -> Self {
+        Error::Io {
+            context: context.into(),
+            source,
+        }
+    }
+
+    pub fn parse(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Error::Parse {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+
+    /// a parse error for a single line, where the caller knows the line number
+    pub fn parse_line(column: usize, message: impl Into<String>) -> Self {
+        Error::parse(0, column, message)
+    }
+
+    pub fn invalid(message: impl Into<String>) -> Self {
+        Error::InvalidState(message.into())
+    }
+
+    /// fill in the line number of a parse error produced by a line parser
+    /// ```
+    /// use aoc2021::error::Error;
+    ///
+    /// let e = Error::parse_line(3, "bad digit").at_line(7);
+    /// assert_eq!("line 7, column 3: bad digit", e.to_string());
+    /// ```
+    pub fn at_line(self, line: usize) -> Self {
+        match self {
+            Error::Parse {
+                line: 0,
+                column,
+                message,
+            } => Error::Parse {
+                line,
+                column,
+                message,
+            },
+            other => other,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io { context, source } => write!(f, "{}: {}", context, source),
+            Error::Parse {
+                line,
+                column,
+                message,
+            } => match (line, column) {
+                (0, 0) => write!(f, "{}", message),
+                (0, c) => write!(f, "column {}: {}", c, message),
+                (l, 0) => write!(f, "line {}: {}", l, message),
+                (l, c) => write!(f, "line {}, column {}: {}", l, c, message),
+            },
+            Error::InvalidState(message) => write!(f, "invalid puzzle state: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!("bad", Error::parse(0, 0, "bad").to_string());
+        assert_eq!("line 2: bad", Error::parse(2, 0, "bad").to_string());
+        assert_eq!("column 4: bad", Error::parse_line(4, "bad").to_string());
+        assert_eq!(
+            "invalid puzzle state: no winner",
+            Error::invalid("no winner").to_string()
+        );
+
+        let e = Error::io("data/none.txt", io::Error::from(io::ErrorKind::NotFound));
+        assert!(e.to_string().starts_with("data/none.txt: "));
+    }
+
+    #[test]
+    fn test_at_line() {
+        assert_matches!(
+            Error::parse_line(1, "x").at_line(3),
+            Error::Parse {
+                line: 3,
+                column: 1,
+                ..
+            }
+        );
+        // a known line is not replaced
+        assert_matches!(
+            Error::parse(2, 1, "x").at_line(3),
+            Error::Parse { line: 2, .. }
+        );
+        assert_matches!(Error::invalid("x").at_line(3), Error::InvalidState(_));
+    }
+}
@@ -0,0 +1,544 @@
+//! Downloading puzzle inputs with the session cookie of a logged in browser
+
+use crate::error::{Error, Result};
+use crate::util::DataDir;
+use std::env;
+use std::fs;
+use std::io;
+use std::io::prelude::*;
+use std::net::TcpStream;
+use std::path::PathBuf;
+use std::time::Duration;
+
+pub const USAGE: &str = "usage: fetch [--session TOKEN] [--url URL] [--data DIR] DAY...
+  --url is a plain http mirror or proxy, such as http://localhost:8080/2021, as the
+  real site only serves https";
+
+/// environment variable holding the session token, when --session isn't given
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+/// environment variable holding the base URL, when --url isn't given
+pub const URL_VAR: &str = "AOC_URL";
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Where the inputs are served from, as in http://host:port/prefix
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BaseUrl {
+    /// an IPv6 address is kept without its brackets
+    pub host: String,
+    pub port: u16,
+    /// empty, or starting with a slash and without a trailing one
+    pub prefix: String,
+}
+
+impl BaseUrl {
+    /// Only plain http is spoken
+    /// ```
+    /// use aoc2021::fetch::BaseUrl;
+    ///
+    /// let url = BaseUrl::parse("http://localhost:8080/2021/").unwrap();
+    /// assert_eq!("localhost", url.host);
+    /// assert_eq!(8080, url.port);
+    /// assert_eq!("http://localhost:8080/2021/day/7/input", url.input(7));
+    /// assert!(BaseUrl::parse("https://adventofcode.com/2021").is_err());
+    ///
+    /// let url = BaseUrl::parse("http://[::1]:8080/2021").unwrap();
+    /// assert_eq!("::1", url.host);
+    /// assert_eq!("http://[::1]:8080/2021/day/7/input", url.input(7));
+    /// ```
+    pub fn parse(url: &str) -> Result<Self> {
+        let rest = url
+            .strip_prefix("http://")
+            .ok_or_else(|| Error::invalid(format!("only http:// URLs can be fetched: {}", url)))?;
+        let (authority, prefix) = match rest.find('/') {
+            Some(i) => rest.split_at(i),
+            None => (rest, ""),
+        };
+        // an IPv6 address has colons of its own, so it comes in brackets
+        let (host, port) = match authority.strip_prefix('[') {
+            Some(bracketed) => bracketed
+                .split_once(']')
+                .ok_or_else(|| Error::invalid(format!("no closing bracket in {}", url)))?,
+            None => authority.split_at(authority.rfind(':').unwrap_or(authority.len())),
+        };
+        let port = match port {
+            "" => 80,
+            port => port
+                .strip_prefix(':')
+                .and_then(|port| port.parse::<u16>().ok())
+                .ok_or_else(|| Error::invalid(format!("not a port: {}", port)))?,
+        };
+        if host.is_empty() {
+            return Err(Error::invalid(format!("no host in {}", url)));
+        }
+        if host.contains(':') && !authority.starts_with('[') {
+            return Err(Error::invalid(format!(
+                "an IPv6 host needs brackets, as in http://[::1]:8080: {}",
+                url
+            )));
+        }
+        Ok(BaseUrl {
+            host: host.to_string(),
+            port,
+            prefix: prefix.trim_end_matches('/').to_string(),
+        })
+    }
+
+    /// the path of a day's input on the server
+    pub fn input_path(&self, day: u8) -> String {
+        format!("{}/day/{}/input", self.prefix, day)
+    }
+
+    /// the host, and the port unless it is the default one
+    pub fn authority(&self) -> String {
+        let host = if self.host.contains(':') {
+            format!("[{}]", self.host)
+        } else {
+            self.host.clone()
+        };
+        match self.port {
+            80 => host,
+            port => format!("{}:{}", host, port),
+        }
+    }
+
+    pub fn input(&self, day: u8) -> String {
+        format!("http://{}{}", self.authority(), self.input_path(day))
+    }
+}
+
+/// Where an input came from
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Fetched {
+    /// it was already in the data directory
+    Cached(PathBuf),
+    Downloaded(PathBuf),
+}
+
+/// Downloads inputs into a data directory
+#[derive(Clone)]
+pub struct Fetcher {
+    pub base: BaseUrl,
+    session: String,
+    pub data: DataDir,
+}
+
+impl Fetcher {
+    pub fn new(base: BaseUrl, session: impl Into<String>, data: DataDir) -> Self {
+        Fetcher {
+            base,
+            session: session.into(),
+            data,
+        }
+    }
+
+    /// Download a day's input, unless it is in the data directory already
+    pub fn fetch(&self, day: u8) -> Result<Fetched> {
+        let path = self.data.puzzle(day);
+        if path.exists() {
+            return Ok(Fetched::Cached(path));
+        }
+
+        let body = self.download(day)?;
+        if body.trim().is_empty() {
+            return Err(Error::invalid(format!(
+                "{} sent an empty input",
+                self.base.input(day)
+            )));
+        }
+
+        // written to the side first, so a failed write can't look cached
+        let context = path.display().to_string();
+        fs::create_dir_all(self.data.root()).map_err(|e| Error::io(context.as_str(), e))?;
+        let partial = path.with_extension("txt.part");
+        fs::write(&partial, body).map_err(|e| Error::io(context.as_str(), e))?;
+        fs::rename(&partial, &path).map_err(|e| Error::io(context.as_str(), e))?;
+        Ok(Fetched::Downloaded(path))
+    }
+
+    fn download(&self, day: u8) -> Result<String> {
+        let url = self.base.input(day);
+        let io_error = |e| Error::io(url.as_str(), e);
+
+        let mut stream =
+            TcpStream::connect((self.base.host.as_str(), self.base.port)).map_err(io_error)?;
+        stream.set_read_timeout(Some(TIMEOUT)).map_err(io_error)?;
+        stream.set_write_timeout(Some(TIMEOUT)).map_err(io_error)?;
+
+        write!(
+            stream,
+            "GET {} HTTP/1.1\r\nHost: {}\r\nCookie: session={}\r\nUser-Agent: aoc2021 fetch\r\nConnection: close\r\n\r\n",
+            self.base.input_path(day),
+            self.base.authority(),
+            self.session
+        )
+        .map_err(io_error)?;
+
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).map_err(io_error)?;
+        let response = parse_response(&response)
+            .ok_or_else(|| Error::invalid(format!("{} sent a malformed response", url)))?;
+        match (response.status, response.location) {
+            (200, _) => (),
+            (300..=399, Some(location)) => {
+                return Err(Error::invalid(format!(
+                    "{} redirected to {}, which can't be followed over plain HTTP; \
+                     --url needs to be a plain HTTP mirror or proxy",
+                    url, location
+                )))
+            }
+            (status, _) => return Err(Error::invalid(format!("{} answered {}", url, status))),
+        }
+        String::from_utf8(response.body)
+            .map_err(|_| Error::invalid(format!("{} sent non-UTF-8 text", url)))
+    }
+}
+
+/// What matters of an HTTP/1.1 response
+struct Response {
+    status: u16,
+    /// where a redirect points
+    location: Option<String>,
+    body: Vec<u8>,
+}
+
+/// Pick apart a whole HTTP/1.1 response, whose body may be chunked
+fn parse_response(response: &[u8]) -> Option<Response> {
+    let end = response.windows(4).position(|w| w == b"\r\n\r\n")?;
+    let head = std::str::from_utf8(&response[..end]).ok()?;
+    let body = &response[end + 4..];
+
+    let mut lines = head.split("\r\n");
+    let status = lines
+        .next()?
+        .strip_prefix("HTTP/1.")?
+        .split(' ')
+        .nth(1)?
+        .parse::<u16>()
+        .ok()?;
+
+    let mut length = None;
+    let mut chunked = false;
+    let mut location = None;
+    for line in lines {
+        let (name, value) = line.split_once(':')?;
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("content-length") {
+            length = Some(value.parse::<usize>().ok()?);
+        } else if name.eq_ignore_ascii_case("transfer-encoding") {
+            chunked = value.eq_ignore_ascii_case("chunked");
+        } else if name.eq_ignore_ascii_case("location") {
+            location = Some(value.to_string());
+        }
+    }
+
+    let body = if chunked {
+        dechunk(body)?
+    } else if let Some(length) = length {
+        body.get(..length)?.to_vec()
+    } else {
+        body.to_vec()
+    };
+    Some(Response {
+        status,
+        location,
+        body,
+    })
+}
+
+fn dechunk(mut body: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    loop {
+        let line_end = body.windows(2).position(|w| w == b"\r\n")?;
+        let size = std::str::from_utf8(&body[..line_end]).ok()?;
+        // chunk extensions follow a semicolon
+        let size = size.split(';').next()?.trim();
+        let size = usize::from_str_radix(size, 16).ok()?;
+        body = &body[line_end + 2..];
+        if size == 0 {
+            return Some(out);
+        }
+        out.extend_from_slice(body.get(..size)?);
+        body = body.get(size + 2..)?;
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Options {
+    pub days: Vec<u8>,
+    pub session: Option<String>,
+    pub url: String,
+    pub data: DataDir,
+}
+
+/// Parse the command line arguments (without the program name)
+pub fn parse_args(args: impl Iterator<Item = String>) -> std::result::Result<Options, String> {
+    let mut days = Vec::new();
+    let mut session = None;
+    let mut url = None;
+    let mut data = None;
+
+    let mut args = args;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-s" | "--session" => session = Some(args.next().ok_or("--session needs a value")?),
+            "-u" | "--url" => url = Some(args.next().ok_or("--url needs a value")?),
+            "-d" | "--data" => {
+                data = Some(DataDir::new(args.next().ok_or("--data needs a value")?));
+            }
+            value => days.push(
+                value
+                    .parse::<u8>()
+                    .map_err(|_| format!("not a day: {}", value))?,
+            ),
+        }
+    }
+
+    if days.is_empty() {
+        return Err("no day given".to_string());
+    }
+    let session = session.or_else(|| env::var(SESSION_VAR).ok());
+    let url = url
+        .or_else(|| env::var(URL_VAR).ok())
+        .ok_or_else(|| format!("no --url given, nor ${}", URL_VAR))?;
+    let data = data.unwrap_or_else(DataDir::locate);
+    Ok(Options {
+        days,
+        session,
+        url,
+        data,
+    })
+}
+
+/// Fetch every day the options ask for, writing a line for each
+pub fn run(options: &Options, out: &mut impl Write) -> Result<()> {
+    let base = BaseUrl::parse(options.url.as_str())?;
+    // nothing needs the session when everything is cached
+    let session = options.session.clone().unwrap_or_default();
+    let fetcher = Fetcher::new(base, session, options.data.clone());
+
+    for day in options.days.iter() {
+        if options.session.is_none() && !fetcher.data.puzzle(*day).exists() {
+            return Err(Error::invalid(format!(
+                "day {} needs a session token, from --session or ${}",
+                day, SESSION_VAR
+            )));
+        }
+        let line = match fetcher.fetch(*day)? {
+            Fetched::Cached(path) => format!("day {}: already in {}", day, path.display()),
+            Fetched::Downloaded(path) => format!("day {}: saved to {}", day, path.display()),
+        };
+        writeln!(out, "{}", line).map_err(|e| Error::io("output", e))?;
+    }
+    Ok(())
+}
+
+/// Entry point for the fetch binary, returns the process exit code
+pub fn main() -> i32 {
+    let options = match parse_args(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(msg) => {
+            eprintln!("{}\n{}", msg, USAGE);
+            return 2;
+        }
+    };
+
+    match run(&options, &mut io::stdout()) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    /// Answers every connection with `response`, keeping the requests
+    struct FakeServer {
+        url: String,
+        requests: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl FakeServer {
+        fn start(response: &'static str) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("http://{}/2021", listener.local_addr().unwrap());
+            let requests = Arc::new(Mutex::new(Vec::new()));
+            let seen = Arc::clone(&requests);
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    let mut stream = stream.unwrap();
+                    let mut request = Vec::new();
+                    let mut buf = [0; 1024];
+                    while !request.ends_with(b"\r\n\r\n") {
+                        match stream.read(&mut buf) {
+                            Ok(0) | Err(_) => break,
+                            Ok(n) => request.extend_from_slice(&buf[..n]),
+                        }
+                    }
+                    seen.lock()
+                        .unwrap()
+                        .push(String::from_utf8_lossy(&request).to_string());
+                    let _ = stream.write_all(response.as_bytes());
+                }
+            });
+            FakeServer { url, requests }
+        }
+
+        fn requests(&self) -> Vec<String> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    fn scratch_dir(name: &str) -> DataDir {
+        DataDir::new(crate::util::scratch_dir(format!("fetch-{}", name).as_str()))
+    }
+
+    fn fetcher(server: &FakeServer, data: &DataDir) -> Fetcher {
+        Fetcher::new(
+            BaseUrl::parse(server.url.as_str()).unwrap(),
+            "53cr3t",
+            data.clone(),
+        )
+    }
+
+    #[test]
+    fn test_parse_url() {
+        assert_matches!(BaseUrl::parse("http://example.com"), Ok(url) => {
+            assert_eq!(80, url.port);
+            assert_eq!("/day/1/input", url.input_path(1));
+        });
+        assert!(BaseUrl::parse("ftp://example.com").is_err());
+        assert!(BaseUrl::parse("http://example.com:http/").is_err());
+        assert!(BaseUrl::parse("http:///2021").is_err());
+
+        assert_matches!(BaseUrl::parse("http://[::1]/2021/"), Ok(url) => {
+            assert_eq!(("::1", 80), (url.host.as_str(), url.port));
+            assert_eq!("http://[::1]/2021/day/1/input", url.input(1));
+        });
+        assert_matches!(BaseUrl::parse("http://[fe80::1]:8080"), Ok(url) => {
+            assert_eq!(("fe80::1", 8080), (url.host.as_str(), url.port));
+            assert_eq!("[fe80::1]:8080", url.authority());
+        });
+        for bad in [
+            "http://::1/2021",
+            "http://::1:8080",
+            "http://[::1",
+            "http://[::1]x",
+            "http://[]:80",
+        ] {
+            assert_matches!(BaseUrl::parse(bad), Err(Error::InvalidState(_)), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_fetch() {
+        let server = FakeServer::start(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: 8\r\n\r\n199\n200\n",
+        );
+        let data = scratch_dir("fetch");
+        let fetcher = fetcher(&server, &data);
+
+        assert_eq!(
+            Fetched::Downloaded(data.puzzle(1)),
+            fetcher.fetch(1).unwrap()
+        );
+        assert_eq!("199\n200\n", fs::read_to_string(data.puzzle(1)).unwrap());
+
+        let requests = server.requests();
+        assert_eq!(1, requests.len());
+        assert!(requests[0].starts_with("GET /2021/day/1/input HTTP/1.1\r\n"));
+        assert!(requests[0].contains("\r\nCookie: session=53cr3t\r\n"));
+        assert!(requests[0].contains("\r\nHost: 127.0.0.1:"));
+
+        // cached now, so the server isn't asked again
+        assert_eq!(Fetched::Cached(data.puzzle(1)), fetcher.fetch(1).unwrap());
+        assert_eq!(1, server.requests().len());
+
+        fs::remove_dir_all(data.root()).unwrap();
+    }
+
+    #[test]
+    fn test_chunked() {
+        let server = FakeServer::start(
+            "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\n1,2,\r\n3;x=y\r\n3\n4\r\n0\r\n\r\n",
+        );
+        let data = scratch_dir("chunked");
+        fetcher(&server, &data).fetch(6).unwrap();
+        assert_eq!("1,2,3\n4", fs::read_to_string(data.puzzle(6)).unwrap());
+        fs::remove_dir_all(data.root()).unwrap();
+    }
+
+    #[test]
+    fn test_refused() {
+        let server = FakeServer::start(
+            "HTTP/1.1 400 Bad Request\r\nContent-Length: 21\r\n\r\nPlease log in first.\n",
+        );
+        let data = scratch_dir("refused");
+        assert_matches!(fetcher(&server, &data).fetch(2), Err(Error::InvalidState(msg)) => {
+            assert!(msg.ends_with("/2021/day/2/input answered 400"), "{}", msg);
+        });
+        // nothing is cached, so the next try asks again
+        assert!(!data.puzzle(2).exists());
+        assert!(fetcher(&server, &data).fetch(2).is_err());
+        assert_eq!(2, server.requests().len());
+
+        assert!(parse_response(b"nonsense").is_none());
+        let _ = fs::remove_dir_all(data.root());
+    }
+
+    #[test]
+    fn test_redirected() {
+        let server = FakeServer::start(
+            "HTTP/1.1 301 Moved Permanently\r\nLocation: https://adventofcode.com/2021/day/3/input\r\nContent-Length: 0\r\n\r\n",
+        );
+        let data = scratch_dir("redirected");
+        assert_matches!(fetcher(&server, &data).fetch(3), Err(Error::InvalidState(msg)) => {
+            assert!(msg.contains("redirected to https://adventofcode.com/2021/day/3/input"), "{}", msg);
+            assert!(msg.contains("--url"), "{}", msg);
+        });
+        assert!(!data.puzzle(3).exists());
+        let _ = fs::remove_dir_all(data.root());
+    }
+
+    #[test]
+    fn test_run() {
+        let server = FakeServer::start("HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n1\n");
+        let data = scratch_dir("run");
+        let args = |values: &[&str]| values.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let root = data.root().to_string_lossy().to_string();
+
+        let options = parse_args(
+            args(&["--url", &server.url, "-d", &root, "-s", "53cr3t", "3", "3"]).into_iter(),
+        )
+        .unwrap();
+        let mut out = Vec::new();
+        run(&options, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert!(lines[0].starts_with("day 3: saved to "));
+        assert!(lines[1].starts_with("day 3: already in "));
+
+        // a cached day needs no session, an uncached one does
+        let mut options = options;
+        options.session = None;
+        assert!(run(&options, &mut Vec::new()).is_ok());
+        options.days = vec![4];
+        assert!(run(&options, &mut Vec::new()).is_err());
+        assert_eq!(1, server.requests().len());
+
+        assert!(parse_args(args(&[]).into_iter()).is_err());
+        if env::var(URL_VAR).is_err() {
+            assert!(parse_args(args(&["3"]).into_iter()).is_err());
+        }
+        fs::remove_dir_all(data.root()).unwrap();
+    }
+}
@@ -0,0 +1,745 @@
+//! Random puzzle inputs, for stress testing and benchmarking at sizes the
+//! real inputs never reach. Everything here is driven by a seeded `Rng`, so
+//! the same seed and size always give the same input.
+
+use crate::util::Grid;
+use std::collections::{HashSet, VecDeque};
+
+/// SplitMix64, which is tiny and good enough to make puzzle inputs
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`, which must not be empty
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0, "nothing is below 0");
+        ((self.next_u64() as u128 * n as u128) >> 64) as usize
+    }
+
+    /// A number in `low..=high`
+    pub fn between(&mut self, low: i64, high: i64) -> i64 {
+        assert!(low <= high, "{} is above {}", low, high);
+        low + self.below((high - low) as usize + 1) as i64
+    }
+
+    /// true `percent` times out of a hundred
+    pub fn chance(&mut self, percent: usize) -> bool {
+        self.below(100) < percent
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    /// Fisher-Yates
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/// A random input for a day, or None if there is no generator for it.
+/// What `size` counts depends on the day, see `default_size`.
+/// ```
+/// use aoc2021::generate;
+///
+/// let raw = generate::generate(4, 42, 3).unwrap();
+/// assert_eq!(raw, generate::generate(4, 42, 3).unwrap());
+/// assert!(aoc2021::day(4).unwrap().validate(&raw).is_empty());
+/// ```
+pub fn generate(day: u8, seed: u64, size: usize) -> Option<String> {
+    let rng = &mut Rng::new(seed);
+    let raw = match day {
+        1 => day1(rng, size),
+        2 => day2(rng, size),
+        3 => {
+            // wide enough that the rows can all be different
+            let width = (12..128).find(|w| 1u128 << w >= 2 * size as u128)?;
+            day3(rng, size, width)
+        }
+        4 => day4(rng, size),
+        5 => day5(rng, size, 1000),
+        6 => day6(rng, size),
+        7 => day7(rng, size, 2000),
+        8 => day8(rng, size),
+        9 => day9(rng, size, size),
+        10 => day10(rng, size),
+        11 => day11(rng, size, size),
+        12 => day12(rng, size, size.div_ceil(3)),
+        13 => day13(rng, size, 12),
+        14 => day14(rng, size, 10),
+        16 => day16(rng, size),
+        17 => day17(rng, size),
+        _ => return None,
+    };
+    Some(raw)
+}
+
+/// About the size of the real puzzle input, in the units `generate` uses
+pub fn default_size(day: u8) -> Option<usize> {
+    let size = match day {
+        1 => 2000,
+        2 | 3 | 7 => 1000,
+        4 | 9 | 10 | 17 => 100,
+        5 | 13 => 500,
+        6 => 300,
+        8 => 200,
+        11 => 10,
+        12 => 6,
+        14 => 20,
+        16 => 60,
+        _ => return None,
+    };
+    Some(size)
+}
+
+/// Depths that mostly increase
+pub fn day1(rng: &mut Rng, depths: usize) -> String {
+    let mut depth = rng.between(100, 200);
+    let mut out = String::new();
+    for _ in 0..depths {
+        out += &format!("{}\n", depth);
+        depth = (depth + rng.between(-10, 20)).max(0);
+    }
+    out
+}
+
+/// Commands that never take the submarine above the surface
+pub fn day2(rng: &mut Rng, commands: usize) -> String {
+    let mut depth = 0;
+    let mut out = String::new();
+    for _ in 0..commands {
+        let value = rng.between(1, 9);
+        let direction = match rng.below(10) {
+            0..=4 => "forward",
+            5..=7 => "down",
+            _ if depth >= value => "up",
+            _ => "down",
+        };
+        match direction {
+            "down" => depth += value,
+            "up" => depth -= value,
+            _ => (),
+        }
+        out += &format!("{} {}\n", direction, value);
+    }
+    out
+}
+
+/// Distinct rows of `width` bits, arranged so that the CO2 scrubber
+/// rating is always down to one row by the last bit.
+pub fn day3(rng: &mut Rng, rows: usize, width: usize) -> String {
+    assert!((1..=128).contains(&width), "{} bits will not fit", width);
+    assert!(
+        width == 128 || rows as u128 <= 1 << width,
+        "{} rows of {} bits cannot all differ",
+        rows,
+        width
+    );
+    let mask = if width == 128 {
+        u128::MAX
+    } else {
+        (1 << width) - 1
+    };
+    let mut seen = HashSet::new();
+    let mut values = Vec::with_capacity(rows);
+    while values.len() < rows {
+        let value = ((rng.next_u64() as u128) << 64 | rng.next_u64() as u128) & mask;
+        if seen.insert(value) {
+            values.push(value);
+        }
+    }
+
+    // keeping the least common bit leaves nothing when the rows left all
+    // agree on it, so make one of them disagree, which can't clash with any
+    // other row as none of them share this prefix
+    let bit = |value: u128, i: usize| value >> (width - 1 - i) & 1;
+    let mut left: Vec<usize> = (0..rows).collect();
+    for i in 0..width {
+        if left.len() < 2 {
+            break;
+        }
+        let ones = left.iter().filter(|r| bit(values[**r], i) == 1).count();
+        if ones == 0 || ones == left.len() {
+            values[left[0]] ^= 1 << (width - 1 - i);
+        }
+        let ones = left.iter().filter(|r| bit(values[**r], i) == 1).count();
+        let keep = if ones < left.len() - ones { 1 } else { 0 };
+        left.retain(|r| bit(values[*r], i) == keep);
+    }
+
+    values
+        .iter()
+        .map(|v| format!("{:0width$b}\n", v, width = width))
+        .collect()
+}
+
+/// Every number from 0 to 99 drawn, and cards of 25 of them, so every card
+/// wins eventually
+pub fn day4(rng: &mut Rng, cards: usize) -> String {
+    let mut numbers: Vec<u8> = (0..100).collect();
+    rng.shuffle(&mut numbers);
+    let draws: Vec<String> = numbers.iter().map(|n| n.to_string()).collect();
+    let mut out = draws.join(",") + "\n";
+
+    for _ in 0..cards {
+        rng.shuffle(&mut numbers);
+        out.push('\n');
+        for row in numbers[..25].chunks(5) {
+            let row: Vec<String> = row.iter().map(|n| format!("{:>2}", n)).collect();
+            out += &(row.join(" ") + "\n");
+        }
+    }
+    out
+}
+
+/// Horizontal, vertical and 45 degree lines within a square of `extent`
+pub fn day5(rng: &mut Rng, lines: usize, extent: usize) -> String {
+    assert!(extent > 1, "lines need room");
+    let extent = extent as i64;
+    let mut out = String::new();
+    for _ in 0..lines {
+        let (x1, y1) = (rng.between(0, extent - 1), rng.between(0, extent - 1));
+        let (x2, y2) = match rng.below(3) {
+            0 => (rng.between(0, extent - 1), y1),
+            1 => (x1, rng.between(0, extent - 1)),
+            _ => {
+                let (dx, dy) = (*rng.choose(&[-1, 1]), *rng.choose(&[-1, 1]));
+                let room = |start: i64, d: i64| if d > 0 { extent - 1 - start } else { start };
+                let length = rng.between(0, room(x1, dx).min(room(y1, dy)));
+                (x1 + dx * length, y1 + dy * length)
+            }
+        };
+        out += &format!("{},{} -> {},{}\n", x1, y1, x2, y2);
+    }
+    out
+}
+
+/// Lanternfish timers, which start between 1 and 5
+pub fn day6(rng: &mut Rng, fish: usize) -> String {
+    let timers: Vec<String> = (0..fish).map(|_| rng.between(1, 5).to_string()).collect();
+    timers.join(",") + "\n"
+}
+
+/// Crab positions, bunched towards the low end like the real ones
+pub fn day7(rng: &mut Rng, crabs: usize, spread: usize) -> String {
+    let positions: Vec<String> = (0..crabs)
+        .map(|_| (rng.below(spread + 1) * rng.below(spread + 1) / spread.max(1)).to_string())
+        .collect();
+    positions.join(",") + "\n"
+}
+
+const SEGMENTS: [&str; 10] = [
+    "abcefg", "cf", "acdeg", "acdfg", "bcdf", "abdfg", "abdefg", "acf", "abcdefg", "abcdfg",
+];
+
+/// Displays each wired up with a different scrambling of the segments
+pub fn day8(rng: &mut Rng, displays: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..displays {
+        let mut wires: Vec<char> = ('a'..='g').collect();
+        rng.shuffle(&mut wires);
+        let pattern = |rng: &mut Rng, digit: usize| {
+            let mut lit: Vec<char> = SEGMENTS[digit]
+                .chars()
+                .map(|c| wires[c as usize - 'a' as usize])
+                .collect();
+            rng.shuffle(&mut lit);
+            lit.into_iter().collect::<String>()
+        };
+        let mut digits: Vec<usize> = (0..10).collect();
+        rng.shuffle(&mut digits);
+        let signal: Vec<String> = digits.iter().map(|d| pattern(rng, *d)).collect();
+        let output: Vec<String> = (0..4)
+            .map(|_| {
+                let digit = rng.below(10);
+                pattern(rng, digit)
+            })
+            .collect();
+        out += &format!("{} | {}\n", signal.join(" "), output.join(" "));
+    }
+    out
+}
+
+/// Basins which rise away from a single low point each, walled off from
+/// one another by 9s, like the real heightmaps
+pub fn day9(rng: &mut Rng, width: usize, height: usize) -> String {
+    // grow every basin out from its low point at once, a step at a time
+    let mut owner: Vec<Vec<Option<(usize, usize)>>> = vec![vec![None; width]; height];
+    let mut next = VecDeque::new();
+    for _ in 0..(width * height / 50).max(1) {
+        let (x, y) = (rng.below(width.max(1)), rng.below(height.max(1)));
+        if height > 0 && width > 0 && owner[y][x].is_none() {
+            owner[y][x] = Some((next.len(), 0));
+            next.push_back((x, y));
+        }
+    }
+    while let Some((x, y)) = next.pop_front() {
+        let (basin, distance) = owner[y][x].unwrap();
+        for (nx, ny) in [
+            (x + 1, y),
+            (x.wrapping_sub(1), y),
+            (x, y + 1),
+            (x, y.wrapping_sub(1)),
+        ] {
+            if nx < width && ny < height && owner[ny][nx].is_none() {
+                owner[ny][nx] = Some((basin, distance + 1));
+                next.push_back((nx, ny));
+            }
+        }
+    }
+
+    let steepness = rng.between(1, 3) as usize;
+    let cells: Vec<Vec<u8>> = (0..height)
+        .map(|y| {
+            (0..width)
+                .map(|x| {
+                    let (basin, distance) = owner[y][x].unwrap();
+                    let border = [(x + 1, y), (x, y + 1)].iter().any(|(nx, ny)| {
+                        *nx < width && *ny < height && owner[*ny][*nx].unwrap().0 != basin
+                    });
+                    if border {
+                        9
+                    } else {
+                        distance.div_ceil(steepness).min(8) as u8
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    digit_rows(&cells)
+}
+
+fn digit_rows(cells: &[Vec<u8>]) -> String {
+    cells
+        .iter()
+        .map(|row| row.iter().map(|d| (b'0' + d) as char).collect::<String>() + "\n")
+        .collect()
+}
+
+/// Lines of brackets which are either corrupted or incomplete, the first
+/// always incomplete. At most 20 are ever open, as the completion score
+/// of more would overflow.
+pub fn day10(rng: &mut Rng, lines: usize) -> String {
+    const PAIRS: [(char, char); 4] = [('(', ')'), ('[', ']'), ('{', '}'), ('<', '>')];
+    let mut out = String::new();
+    for i in 0..lines {
+        let corrupt = i > 0 && rng.chance(50);
+        let length = rng.between(10, 100);
+        let mut open: Vec<usize> = Vec::new();
+        let mut line = String::new();
+        let mut corrupted = false;
+        for _ in 0..length {
+            if open.is_empty() || (open.len() < 20 && rng.chance(55)) {
+                let kind = rng.below(PAIRS.len());
+                open.push(kind);
+                line.push(PAIRS[kind].0);
+            } else {
+                let kind = open.pop().unwrap();
+                if corrupt && !corrupted && rng.chance(10) {
+                    line.push(PAIRS[(kind + 1 + rng.below(3)) % 4].1);
+                    corrupted = true;
+                } else {
+                    line.push(PAIRS[kind].1);
+                }
+            }
+        }
+        if corrupt && !corrupted {
+            let kind = open.pop().unwrap_or(0);
+            line.push(PAIRS[(kind + 1) % 4].1);
+        } else if !corrupt && open.is_empty() {
+            line.push(PAIRS[rng.below(4)].0);
+        }
+        out += &(line + "\n");
+    }
+    out
+}
+
+/// Octopus energy levels. Real inputs all flash together before long and
+/// most random grids don't, so this tries grids until one does within the
+/// rounds day 11 allows. Beyond about 20x20 that is rare, and after 100
+/// tries the last grid is returned anyway.
+pub fn day11(rng: &mut Rng, width: usize, height: usize) -> String {
+    let mut raw = String::new();
+    for _ in 0..100 {
+        let cells: Vec<Vec<u8>> = (0..height)
+            .map(|_| (0..width).map(|_| rng.below(10) as u8).collect())
+            .collect();
+        raw = digit_rows(&cells);
+        let grid = Grid::parse_digits(raw.as_str()).expect("only digits");
+        if crate::day11::part2(&grid).is_ok() {
+            break;
+        }
+    }
+    raw
+}
+
+/// A name that isn't taken yet, of two letters until those start running
+/// out and longer after that
+fn cave_name(
+    rng: &mut Rng,
+    letters: std::ops::RangeInclusive<char>,
+    taken: &HashSet<String>,
+) -> String {
+    let letters: Vec<char> = letters.collect();
+    // at least twice as many names as there are taken, so a few tries will do
+    let mut len = 2;
+    while letters
+        .len()
+        .checked_pow(len)
+        .is_some_and(|names| names < 2 * (taken.len() + 1))
+    {
+        len += 1;
+    }
+    loop {
+        let name: String = (0..len).map(|_| *rng.choose(&letters)).collect();
+        if !taken.contains(&name) && name != "start" && name != "end" {
+            return name;
+        }
+    }
+}
+
+/// A connected cave system. Big caves are never linked to each other,
+/// which would make for endless paths.
+pub fn day12(rng: &mut Rng, small: usize, big: usize) -> String {
+    let mut names = HashSet::new();
+    let mut smalls = Vec::new();
+    for _ in 0..small.max(1) {
+        let name = cave_name(rng, 'a'..='z', &names);
+        names.insert(name.clone());
+        smalls.push(name);
+    }
+    let mut bigs = Vec::new();
+    for _ in 0..big {
+        let name = cave_name(rng, 'A'..='Z', &names);
+        names.insert(name.clone());
+        bigs.push(name);
+    }
+
+    let mut links: Vec<(String, String)> = Vec::new();
+    let mut linked = HashSet::new();
+    let mut link = |a: &String, b: &String| {
+        if a != b && linked.insert((a.min(b).clone(), a.max(b).clone())) {
+            links.push((a.clone(), b.clone()));
+        }
+    };
+
+    // a chain through every small cave keeps them all reachable
+    for i in 1..smalls.len() {
+        let earlier = rng.below(i);
+        link(&smalls[i], &smalls[earlier]);
+    }
+    for cave in bigs.iter() {
+        for _ in 0..rng.between(1, 3) {
+            link(cave, rng.choose(&smalls));
+        }
+    }
+    let start = "start".to_string();
+    let end = "end".to_string();
+    let caves: Vec<String> = smalls.iter().chain(bigs.iter()).cloned().collect();
+    for _ in 0..rng.between(1, 2) {
+        link(&start, rng.choose(&caves));
+        link(rng.choose(&caves), &end);
+    }
+
+    rng.shuffle(&mut links);
+    links
+        .iter()
+        .map(|(a, b)| format!("{}-{}\n", a, b))
+        .collect()
+}
+
+/// Dots on paper which is always folded exactly in half, so that each fold
+/// line is in the middle of what is left of the paper. No dot is ever on a
+/// fold line.
+pub fn day13(rng: &mut Rng, dots: usize, folds: usize) -> String {
+    // work backwards from the folded size, unfolding each time
+    let (mut width, mut height) = (rng.between(5, 40), rng.between(5, 10));
+    let mut commands = Vec::with_capacity(folds);
+    let mut along_x = rng.chance(50);
+    for _ in 0..folds {
+        if along_x {
+            commands.push(('x', width));
+            width = width * 2 + 1;
+        } else {
+            commands.push(('y', height));
+            height = height * 2 + 1;
+        }
+        along_x = !along_x;
+    }
+    commands.reverse();
+
+    let on_fold = |(mut x, mut y): (i64, i64)| {
+        for (axis, at) in commands.iter() {
+            let c = if *axis == 'x' { &mut x } else { &mut y };
+            if *c == *at {
+                return true;
+            }
+            if *c > *at {
+                *c = 2 * at - *c;
+            }
+        }
+        false
+    };
+
+    let dot = |rng: &mut Rng, x: Option<i64>, y: Option<i64>| loop {
+        let dot = (
+            x.unwrap_or_else(|| rng.between(0, width - 1)),
+            y.unwrap_or_else(|| rng.between(0, height - 1)),
+        );
+        if !on_fold(dot) {
+            return dot;
+        }
+    };
+
+    // the far edges need dots on them for the paper to be this big
+    let mut placed = vec![
+        dot(rng, Some(width - 1), None),
+        dot(rng, None, Some(height - 1)),
+    ];
+    let mut seen: HashSet<(i64, i64)> = placed.iter().copied().collect();
+    let wanted = dots.min((width * height / 4) as usize);
+    while placed.len() < wanted {
+        let next = dot(rng, None, None);
+        if seen.insert(next) {
+            placed.push(next);
+        }
+    }
+    rng.shuffle(&mut placed);
+
+    let mut out: String = placed
+        .iter()
+        .map(|(x, y)| format!("{},{}\n", x, y))
+        .collect();
+    out.push('\n');
+    for (axis, at) in commands {
+        out += &format!("fold along {}={}\n", axis, at);
+    }
+    out
+}
+
+/// A template of up to 26 elements, and a rule for every pair of them
+pub fn day14(rng: &mut Rng, template: usize, elements: usize) -> String {
+    let mut letters: Vec<char> = ('A'..='Z').collect();
+    rng.shuffle(&mut letters);
+    letters.truncate(elements.clamp(1, 26));
+
+    let mut out: String = (0..template.max(2))
+        .map(|_| *rng.choose(&letters))
+        .collect();
+    out += "\n\n";
+    for a in letters.iter() {
+        for b in letters.iter() {
+            out += &format!("{}{} -> {}\n", a, b, rng.choose(&letters));
+        }
+    }
+    out
+}
+
+fn push_bits(bits: &mut Vec<bool>, value: u64, width: usize) {
+    bits.extend((0..width).rev().map(|i| value >> i & 1 == 1));
+}
+
+/// One packet of about `budget` packets, including the ones inside it
+fn packet(rng: &mut Rng, budget: &mut usize, bits: &mut Vec<bool>) {
+    push_bits(bits, rng.below(8) as u64, 3);
+    if *budget <= 1 {
+        *budget = 0;
+        literal(rng, bits, 20);
+        return;
+    }
+    *budget -= 1;
+
+    let kind = *rng.choose(&[0, 1, 2, 3, 5, 6, 7]);
+    push_bits(bits, kind, 3);
+    let count = match kind {
+        5..=7 => 2,
+        _ => rng.between(1, 4) as usize,
+    };
+
+    let mut inner = Vec::new();
+    for _ in 0..count {
+        if kind == 1 {
+            // keeps products from overflowing, however deep they are
+            push_bits(&mut inner, rng.below(8) as u64, 3);
+            literal(rng, &mut inner, 8);
+        } else {
+            packet(rng, budget, &mut inner);
+        }
+    }
+
+    if inner.len() < 1 << 15 && rng.chance(50) {
+        bits.push(false);
+        push_bits(bits, inner.len() as u64, 15);
+    } else {
+        bits.push(true);
+        push_bits(bits, count as u64, 11);
+    }
+    bits.append(&mut inner);
+}
+
+/// The type and value of a literal of up to `width` bits
+fn literal(rng: &mut Rng, bits: &mut Vec<bool>, width: u32) {
+    push_bits(bits, 4, 3);
+    let value = rng.next_u64() >> (64 - rng.between(1, width as i64));
+    let groups = (64 - value.leading_zeros()).max(1).div_ceil(4) as usize;
+    for i in (0..groups).rev() {
+        bits.push(i > 0);
+        push_bits(bits, value >> (i * 4) & 0xf, 4);
+    }
+}
+
+/// A BITS transmission of about `packets` packets, as hex
+pub fn day16(rng: &mut Rng, packets: usize) -> String {
+    let mut bits = Vec::new();
+    let mut budget = packets.max(1);
+    packet(rng, &mut budget, &mut bits);
+    while bits.len() % 4 != 0 {
+        bits.push(false);
+    }
+    let hex: String = bits
+        .chunks(4)
+        .map(|nibble| {
+            let value = nibble.iter().fold(0, |acc, b| acc << 1 | *b as u32);
+            char::from_digit(value, 16).unwrap().to_ascii_uppercase()
+        })
+        .collect();
+    hex + "\n"
+}
+
+/// A target area about `reach` away, which the day 17 search covers as
+/// long as `reach` is no more than 200
+pub fn day17(rng: &mut Rng, reach: usize) -> String {
+    let reach = reach.clamp(10, 200) as i64;
+    let x1 = rng.between(reach / 2, reach);
+    let x2 = x1 + rng.between(5, reach / 3 + 5);
+    let y1 = -rng.between(reach / 2, reach);
+    let y2 = y1 + rng.between(5, reach / 4 + 5);
+    format!("target area: x={}..{}, y={}..{}\n", x1, x2, y1, y2)
+}
+
+/// For the tests: small generated inputs for every day with a generator should validate and solve
+#[cfg(test)]
+pub fn check_generated_inputs_solve(days: &[&dyn crate::solution::Day]) {
+    use crate::solution::Part;
+
+    for day in days {
+        // small enough to keep the test quick
+        let Some(size) = default_size(day.number()) else {
+            continue;
+        };
+        let size = size.min(50);
+        for seed in 0..3 {
+            let raw = generate(day.number(), seed, size).unwrap();
+            let problems = day.validate(raw.as_str());
+            assert!(
+                problems.is_empty(),
+                "day {} seed {}: {:?}\n{}",
+                day.number(),
+                seed,
+                problems,
+                raw
+            );
+            for part in [Part::One, Part::Two] {
+                if let Err(e) = day.run(raw.as_str(), part) {
+                    panic!(
+                        "day {} seed {} part {}: {}",
+                        day.number(),
+                        seed,
+                        part.number(),
+                        e
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_rng() {
+        let mut rng = Rng::new(7);
+        let first: Vec<u64> = (0..4).map(|_| rng.next_u64()).collect();
+        let mut again = Rng::new(7);
+        assert_eq!(first, (0..4).map(|_| again.next_u64()).collect::<Vec<_>>());
+        assert_ne!(first[0], Rng::new(8).next_u64());
+
+        for _ in 0..1000 {
+            assert!(rng.below(3) < 3);
+            assert!((-2..=2).contains(&rng.between(-2, 2)));
+        }
+        assert_eq!(5, rng.between(5, 5));
+
+        let mut items: Vec<u8> = (0..20).collect();
+        rng.shuffle(&mut items);
+        assert_ne!((0..20).collect::<Vec<u8>>(), items);
+        items.sort();
+        assert_eq!((0..20).collect::<Vec<u8>>(), items);
+    }
+
+    #[test]
+    fn test_generated_inputs_solve() {
+        check_generated_inputs_solve(crate::DAYS);
+        assert_eq!(None, generate(26, 0, 10));
+    }
+
+    #[test]
+    fn test_seeds() {
+        assert_eq!(generate(8, 1, 5), generate(8, 1, 5));
+        assert_ne!(generate(8, 1, 5), generate(8, 2, 5));
+        assert_eq!(5, generate(8, 1, 5).unwrap().lines().count());
+    }
+
+    #[test]
+    fn test_day3_rows_differ() {
+        let raw = day3(&mut Rng::new(3), 16, 4);
+        let rows: HashSet<&str> = raw.lines().collect();
+        assert_eq!(16, rows.len());
+    }
+
+    #[test]
+    fn test_day12_names_run_long() {
+        // more small caves than there are two letter names
+        let raw = day12(&mut Rng::new(12), 700, 10);
+        let caves: HashSet<&str> = raw.lines().flat_map(|l| l.split('-')).collect();
+        assert_eq!(700 + 10 + 2, caves.len());
+        assert!(caves.iter().any(|c| c.len() > 2));
+        assert!(generate(12, 1, 700).is_some());
+    }
+
+    #[test]
+    fn test_day13_folds_in_half() {
+        let raw = day13(&mut Rng::new(5), 30, 4);
+        let (dots, folds) = raw.split_once("\n\n").unwrap();
+        let width = dots
+            .lines()
+            .map(|d| d.split(',').next().unwrap().parse::<i64>().unwrap())
+            .max()
+            .unwrap()
+            + 1;
+        let first_x = folds
+            .lines()
+            .find_map(|f| f.strip_prefix("fold along x="))
+            .unwrap();
+        assert_eq!(width, first_x.parse::<i64>().unwrap() * 2 + 1);
+    }
+}
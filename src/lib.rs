@@ -1,10 +1,11 @@
 // https://github.com/rust-lang/rust/issues/44342
-#[cfg_attr(test, macro_use)]
-extern crate lazy_static;
-
 #[cfg_attr(test, macro_use)]
 extern crate assert_matches;
 
+pub mod alloc_stats;
+pub mod answers;
+pub mod bench;
+pub mod cli;
 pub mod day1;
 pub mod day10;
 pub mod day11;
@@ -21,4 +22,56 @@ pub mod day6;
 pub mod day7;
 pub mod day8;
 pub mod day9;
+#[cfg(test)]
+mod differential;
+pub mod error;
+pub mod fetch;
+pub mod generate;
+pub mod log;
+pub mod report;
+pub mod run_all;
+pub mod scaffold;
+pub mod solution;
+pub mod stream;
 pub mod util;
+pub mod watch;
+
+use solution::Day;
+
+/// every implemented day, in order
+pub static DAYS: &[&dyn Day] = &[
+    &day1::Day1,
+    &day2::Day2,
+    &day3::Day3,
+    &day4::Day4,
+    &day5::Day5,
+    &day6::Day6,
+    &day7::Day7,
+    &day8::Day8,
+    &day9::Day9,
+    &day10::Day10,
+    &day11::Day11,
+    &day12::Day12,
+    &day13::Day13,
+    &day14::Day14,
+    &day16::Day16,
+    &day17::Day17,
+];
+
+/// look up a day by its number
+pub fn day(number: u8) -> Option<&'static dyn Day> {
+    DAYS.iter().find(|d| d.number() == number).copied()
+}
+
+/// The days with these numbers, or every implemented day if there are none
+pub fn select_days(numbers: &[u8]) -> error::Result<Vec<&'static dyn Day>> {
+    if numbers.is_empty() {
+        return Ok(DAYS.to_vec());
+    }
+    numbers
+        .iter()
+        .map(|n| {
+            day(*n).ok_or_else(|| error::Error::invalid(format!("day {} is not implemented", n)))
+        })
+        .collect()
+}
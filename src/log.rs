@@ -0,0 +1,252 @@
+//! Diagnostics from the solvers, silent unless asked for.
+//!
+//! What is shown is set by a filter such as `debug` or `info,day17=trace`:
+//! a default level followed by levels for particular days. It is read from
+//! `$AOC_LOG` the first time anything is logged, or set with `init`.
+
+use std::fmt;
+use std::io::prelude::*;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::RwLock;
+
+/// environment variable holding the log filter
+pub const LOG_VAR: &str = "AOC_LOG";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error = 1,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    fn parse(name: &str) -> Option<Option<Level>> {
+        match name.to_ascii_lowercase().as_str() {
+            "off" => Some(None),
+            "error" => Some(Some(Level::Error)),
+            "warn" => Some(Some(Level::Warn)),
+            "info" => Some(Some(Level::Info)),
+            "debug" => Some(Some(Level::Debug)),
+            "trace" => Some(Some(Level::Trace)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Which messages are shown; a level of None shows nothing
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Filter {
+    default: Option<Level>,
+    targets: Vec<(String, Option<Level>)>,
+}
+
+impl Filter {
+    /// ```
+    /// use aoc2021::log::{Filter, Level};
+    ///
+    /// let filter = Filter::parse("warn,day17=trace,day4=off").unwrap();
+    /// assert!(filter.enabled("day17", Level::Trace));
+    /// assert!(filter.enabled("day9", Level::Error));
+    /// assert!(!filter.enabled("day9", Level::Info));
+    /// assert!(!filter.enabled("day4", Level::Error));
+    /// ```
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut filter = Filter::default();
+        for item in spec.split(',').map(str::trim).filter(|i| !i.is_empty()) {
+            let level = |name: &str| {
+                Level::parse(name).ok_or_else(|| format!("unknown log level: {}", name))
+            };
+            match item.split_once('=') {
+                Some((target, name)) => filter
+                    .targets
+                    .push((target.trim().to_string(), level(name.trim())?)),
+                None => filter.default = level(item)?,
+            }
+        }
+        Ok(filter)
+    }
+
+    pub fn enabled(&self, target: &str, level: Level) -> bool {
+        let allowed = self
+            .targets
+            .iter()
+            .rev()
+            .find(|(t, _)| t == target)
+            .map_or(self.default, |(_, l)| *l);
+        allowed.is_some_and(|allowed| level <= allowed)
+    }
+
+    /// the most detailed level shown for any target, 0 when nothing is
+    fn max_level(&self) -> u8 {
+        self.targets
+            .iter()
+            .map(|(_, l)| *l)
+            .chain([self.default])
+            .map(|l| l.map_or(0, |l| l as u8))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+const UNSET: u8 = u8::MAX;
+
+static FILTER: RwLock<Option<Filter>> = RwLock::new(None);
+/// lets most messages be dropped without taking the lock
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(UNSET);
+
+/// Replace the filter, e.g. from a command line flag
+pub fn init(spec: &str) -> Result<(), String> {
+    set_filter(Filter::parse(spec)?);
+    Ok(())
+}
+
+pub fn set_filter(filter: Filter) {
+    let mut current = FILTER.write().unwrap_or_else(|e| e.into_inner());
+    MAX_LEVEL.store(filter.max_level(), Ordering::Relaxed);
+    *current = Some(filter);
+}
+
+fn init_from_env() {
+    let filter = match std::env::var(LOG_VAR) {
+        Ok(spec) => Filter::parse(spec.as_str()).unwrap_or_else(|e| {
+            eprintln!("ignoring ${}: {}", LOG_VAR, e);
+            Filter::default()
+        }),
+        Err(_) => Filter::default(),
+    };
+    let mut current = FILTER.write().unwrap_or_else(|e| e.into_inner());
+    // unless init got there first
+    if current.is_none() {
+        MAX_LEVEL.store(filter.max_level(), Ordering::Relaxed);
+        *current = Some(filter);
+    }
+}
+
+/// The day a message is about, from the module that logged it, so
+/// "aoc2021::day17" is "day17"
+pub fn target(module_path: &str) -> &str {
+    module_path.split("::").nth(1).unwrap_or(module_path)
+}
+
+pub fn enabled(module_path: &str, level: Level) -> bool {
+    if MAX_LEVEL.load(Ordering::Relaxed) == UNSET {
+        init_from_env();
+    }
+    if level as u8 > MAX_LEVEL.load(Ordering::Relaxed) {
+        return false;
+    }
+    FILTER
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .is_some_and(|f| f.enabled(target(module_path), level))
+}
+
+/// Write one message to stderr, which is where the macros send them
+pub fn write(module_path: &str, level: Level, args: fmt::Arguments<'_>) {
+    let _ = writeln!(
+        std::io::stderr().lock(),
+        "[{} {}] {}",
+        level,
+        target(module_path),
+        args
+    );
+}
+
+/// Log a message at the given level, for the day of the calling module
+#[macro_export]
+macro_rules! log {
+    ($level:expr, $($arg:tt)+) => {
+        if $crate::log::enabled(module_path!(), $level) {
+            $crate::log::write(module_path!(), $level, format_args!($($arg)+));
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! error {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Error, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Warn, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Info, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Debug, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Trace, $($arg)+) };
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Filter::default(), Filter::parse("").unwrap());
+        assert_eq!(0, Filter::parse("off").unwrap().max_level());
+        assert_eq!(
+            Level::Trace as u8,
+            Filter::parse("info, day17=trace").unwrap().max_level()
+        );
+
+        let filter = Filter::parse("DEBUG").unwrap();
+        assert!(filter.enabled("day1", Level::Debug));
+        assert!(!filter.enabled("day1", Level::Trace));
+
+        // the last mention of a day wins
+        let filter = Filter::parse("day4=trace,day4=info").unwrap();
+        assert!(filter.enabled("day4", Level::Info));
+        assert!(!filter.enabled("day4", Level::Debug));
+        assert!(!filter.enabled("day5", Level::Error));
+
+        assert!(Filter::parse("loud").is_err());
+        assert!(Filter::parse("day4=loud").is_err());
+    }
+
+    #[test]
+    fn test_enabled() {
+        // no other test logs for day 99, so this doesn't change what they see
+        init("day99=debug").unwrap();
+        assert!(enabled("aoc2021::day99", Level::Debug));
+        assert!(!enabled("aoc2021::day99", Level::Trace));
+        assert!(!enabled("aoc2021::day17", Level::Error));
+        assert!(init("day99=loud").is_err());
+        set_filter(Filter::default());
+        assert!(!enabled("aoc2021::day99", Level::Error));
+    }
+
+    #[test]
+    fn test_target() {
+        assert_eq!("day17", target("aoc2021::day17"));
+        assert_eq!("day1", target("aoc2021::day1::analytics"));
+        assert_eq!("aoc2021", target("aoc2021"));
+    }
+}
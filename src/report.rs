@@ -0,0 +1,201 @@
+use std::fmt;
+
+/// A JSON value, just enough to describe answers and their intermediates
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Int(i128),
+    UInt(u128),
+    Str(String),
+    List(Vec<Value>),
+    /// keeps its fields in the order they were added
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    pub fn object() -> Self {
+        Value::Object(Vec::new())
+    }
+
+    /// Add a field to an object
+    /// ```
+    /// use aoc2021::report::Value;
+    ///
+    /// let value = Value::object().with("depth", 10).with("name", "sub");
+    /// assert_eq!(r#"{"depth":10,"name":"sub"}"#, value.to_string());
+    /// ```
+    pub fn with(mut self, name: &str, value: impl Into<Value>) -> Self {
+        if let Value::Object(fields) = &mut self {
+            fields.push((name.to_string(), value.into()));
+        } else {
+            panic!("only an object has fields");
+        }
+        self
+    }
+}
+
+fn write_str(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+/// Compact JSON
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Null => write!(f, "null"),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Int(i) => write!(f, "{}", i),
+            Value::UInt(u) => write!(f, "{}", u),
+            Value::Str(s) => write_str(f, s),
+            Value::List(values) => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            }
+            Value::Object(fields) => {
+                write!(f, "{{")?;
+                for (i, (name, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_str(f, name)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+macro_rules! from_int {
+    ($variant:ident, $wide:ty, $($t:ty),*) => {
+        $(impl From<$t> for Value {
+            fn from(v: $t) -> Self {
+                Value::$variant(v as $wide)
+            }
+        })*
+    };
+}
+
+from_int!(Int, i128, i32, i64, i128);
+from_int!(UInt, u128, u8, u32, u64, u128, usize);
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Value::Bool(b)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::Str(s.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::Str(s)
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(v: Option<T>) -> Self {
+        v.map_or(Value::Null, Into::into)
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(values: Vec<T>) -> Self {
+        Value::List(values.into_iter().map(Into::into).collect())
+    }
+}
+
+/// pairs such as (x, y) become two element lists
+impl<A: Into<Value>, B: Into<Value>> From<(A, B)> for Value {
+    fn from((a, b): (A, B)) -> Self {
+        Value::List(vec![a.into(), b.into()])
+    }
+}
+
+/// An answer along with the named values it was worked out from
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Report {
+    pub answer: String,
+    pub fields: Vec<(String, Value)>,
+}
+
+impl Report {
+    pub fn new(answer: impl fmt::Display) -> Self {
+        Report {
+            answer: answer.to_string(),
+            fields: Vec::new(),
+        }
+    }
+
+    pub fn with(mut self, name: &str, value: impl Into<Value>) -> Self {
+        self.fields.push((name.to_string(), value.into()));
+        self
+    }
+
+    /// the intermediate values as a JSON object
+    pub fn fields(&self) -> Value {
+        Value::Object(self.fields.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_json() {
+        assert_eq!("null", Value::Null.to_string());
+        assert_eq!("-3", Value::from(-3).to_string());
+        assert_eq!(
+            "340282366920938463463374607431768211455",
+            Value::from(u128::MAX).to_string()
+        );
+        assert_eq!(
+            r#""a\"b\\c\nd\u0001""#,
+            Value::from("a\"b\\c\nd\u{1}").to_string()
+        );
+        assert_eq!(
+            "[[1,2],[3,-4]]",
+            Value::from(vec![(1, 2), (3, -4)]).to_string()
+        );
+        assert_eq!("[]", Value::from(Vec::<i32>::new()).to_string());
+        assert_eq!("{}", Value::object().to_string());
+        assert_eq!(
+            r#"{"a":{"b":[true,null]}}"#,
+            Value::object()
+                .with("a", Value::object().with("b", vec![Some(true), None]))
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_report() {
+        let report = Report::new(150).with("depth", 10).with("horiz", 15);
+        assert_eq!("150", report.answer);
+        assert_eq!(r#"{"depth":10,"horiz":15}"#, report.fields().to_string());
+    }
+}
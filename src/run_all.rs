@@ -0,0 +1,404 @@
+use crate::answers::{Answers, Verdict};
+use crate::error::{Error, Result};
+use crate::solution::{Day, Part};
+use crate::util::DataDir;
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::io;
+use std::io::prelude::*;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, Once};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub const USAGE: &str = "usage: run-all [--jobs N] [--data DIR] [DAY...]";
+
+/// One part of one day to solve for one input file
+pub struct Job {
+    pub day: &'static dyn Day,
+    /// the input's file name, e.g. day12_test_1.txt
+    pub input: String,
+    pub raw: String,
+    pub part: Part,
+}
+
+/// Why a job has no answer
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Failure {
+    Error(String),
+    Panic(String),
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Failure::Error(msg) => write!(f, "error: {}", msg),
+            Failure::Panic(msg) => write!(f, "panicked: {}", msg),
+        }
+    }
+}
+
+/// What came of a job
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Outcome {
+    pub day: u8,
+    pub input: String,
+    pub part: Part,
+    /// parsing and solving together
+    pub elapsed: Duration,
+    pub answer: std::result::Result<String, Failure>,
+}
+
+/// Both parts of every day for every input file, in order
+pub fn collect_jobs(data: &DataDir, days: &[&'static dyn Day]) -> Result<Vec<Job>> {
+    let mut jobs = Vec::new();
+    for day in days {
+        for variant in data.variants(day.number())? {
+            let raw = data.read_to_string(variant.day, variant.kind)?;
+            for part in [Part::One, Part::Two] {
+                jobs.push(Job {
+                    day: *day,
+                    input: variant.name(),
+                    raw: raw.clone(),
+                    part,
+                });
+            }
+        }
+    }
+    Ok(jobs)
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(msg) => *msg,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(msg) => msg.to_string(),
+            Err(_) => "unknown panic".to_string(),
+        },
+    }
+}
+
+thread_local! {
+    /// whether this thread is running a job right now
+    static IN_JOB: Cell<bool> = const { Cell::new(false) };
+    /// where the job this thread was running panicked
+    static PANIC_LOCATION: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Panics in jobs are reported with their outcome, anything else goes to the old hook
+fn quiet_job_panics() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if IN_JOB.with(Cell::get) {
+                let location = info.location().map(|l| l.to_string());
+                PANIC_LOCATION.with(|p| *p.borrow_mut() = location);
+            } else {
+                previous(info);
+            }
+        }));
+    });
+}
+
+fn run_job(job: &Job) -> Outcome {
+    let start = Instant::now();
+    IN_JOB.with(|j| j.set(true));
+    let answer = panic::catch_unwind(AssertUnwindSafe(|| job.day.run(&job.raw, job.part)));
+    IN_JOB.with(|j| j.set(false));
+    let elapsed = start.elapsed();
+    Outcome {
+        day: job.day.number(),
+        input: job.input.clone(),
+        part: job.part,
+        elapsed,
+        answer: match answer {
+            Ok(Ok(answer)) => Ok(answer),
+            Ok(Err(e)) => Err(Failure::Error(e.to_string())),
+            Err(payload) => {
+                let message = panic_message(payload);
+                Err(Failure::Panic(match PANIC_LOCATION.with(|p| p.take()) {
+                    Some(location) => format!("{} at {}", message, location),
+                    None => message,
+                }))
+            }
+        },
+    }
+}
+
+/// Run the jobs on `workers` threads, giving the outcomes in the same order
+pub fn run_jobs(jobs: &[Job], workers: usize) -> Vec<Outcome> {
+    quiet_job_panics();
+    let next = AtomicUsize::new(0);
+    let outcomes = Mutex::new(vec![None; jobs.len()]);
+
+    thread::scope(|scope| {
+        for _ in 0..workers.clamp(1, jobs.len().max(1)) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(job) = jobs.get(i) else {
+                    break;
+                };
+                let outcome = run_job(job);
+                outcomes.lock().unwrap_or_else(|e| e.into_inner())[i] = Some(outcome);
+            });
+        }
+    });
+
+    outcomes
+        .into_inner()
+        .unwrap_or_else(|e| e.into_inner())
+        .into_iter()
+        .map(|o| o.expect("every job was run"))
+        .collect()
+}
+
+/// Write a table of the outcomes, returning whether they all have fine answers
+pub fn write_summary(
+    outcomes: &[Outcome],
+    answers: &Answers,
+    out: &mut impl Write,
+) -> io::Result<bool> {
+    let mut all_good = true;
+    writeln!(
+        out,
+        "{:>3}  {:<18} {:>4}  {:<20} {:>10}  status",
+        "day", "input", "part", "answer", "time ms"
+    )?;
+    for o in outcomes {
+        let (answer, status) = match &o.answer {
+            Ok(answer) => {
+                let verdict = answers.check(o.day, o.part, &o.input, answer);
+                all_good &= !verdict.is_failure();
+                let status = match verdict {
+                    Verdict::Unknown => "unchecked".to_string(),
+                    v => v.to_string(),
+                };
+                (answer.as_str(), status)
+            }
+            Err(failure) => {
+                all_good = false;
+                ("-", failure.to_string())
+            }
+        };
+        writeln!(
+            out,
+            "{:>3}  {:<18} {:>4}  {:<20} {:>10.3}  {}",
+            o.day,
+            o.input,
+            o.part.number(),
+            answer,
+            o.elapsed.as_secs_f64() * 1e3,
+            status
+        )?;
+    }
+    Ok(all_good)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Options {
+    /// every implemented day when empty
+    pub days: Vec<u8>,
+    /// worker threads, by default one per CPU
+    pub jobs: usize,
+    pub data: DataDir,
+}
+
+/// Parse the command line arguments (without the program name)
+pub fn parse_args(args: impl Iterator<Item = String>) -> std::result::Result<Options, String> {
+    let mut days = Vec::new();
+    let mut jobs = None;
+    let mut data = None;
+
+    let mut args = args;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-j" | "--jobs" => {
+                let value = args.next().ok_or("--jobs needs a value")?;
+                jobs = match value.parse::<usize>() {
+                    Ok(n) if n > 0 => Some(n),
+                    _ => return Err(format!("not a number of jobs: {}", value)),
+                };
+            }
+            "-d" | "--data" => {
+                data = Some(DataDir::new(args.next().ok_or("--data needs a value")?));
+            }
+            value => days.push(
+                value
+                    .parse::<u8>()
+                    .map_err(|_| format!("not a day: {}", value))?,
+            ),
+        }
+    }
+
+    let jobs = jobs.unwrap_or_else(|| thread::available_parallelism().map_or(1, |n| n.get()));
+    let data = data.unwrap_or_else(DataDir::locate);
+    Ok(Options { days, jobs, data })
+}
+
+/// Solve everything the options ask for, returning whether it all went well
+pub fn run(options: &Options, out: &mut impl Write) -> Result<bool> {
+    let days = crate::select_days(&options.days)?;
+    let jobs = collect_jobs(&options.data, &days)?;
+    let answers = Answers::load(&options.data)?;
+    let outcomes = run_jobs(&jobs, options.jobs);
+    write_summary(&outcomes, &answers, out).map_err(|e| Error::io("output", e))
+}
+
+/// Entry point for the run-all binary, returns the process exit code
+pub fn main() -> i32 {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(msg) => {
+            eprintln!("{}\n{}", msg, USAGE);
+            return 2;
+        }
+    };
+
+    match run(&options, &mut io::stdout()) {
+        Ok(true) => 0,
+        Ok(false) => 1,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::solution::Solution;
+
+    fn args(values: &[&str]) -> impl Iterator<Item = String> {
+        values
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<String>>()
+            .into_iter()
+    }
+
+    /// part 1 counts the lines, part 2 panics
+    struct Fragile;
+
+    impl Solution for Fragile {
+        const DAY: u8 = 99;
+
+        type Input = Vec<String>;
+        type Answer1 = usize;
+        type Answer2 = usize;
+
+        fn parse(raw: &str) -> Result<Self::Input> {
+            Ok(raw.lines().map(String::from).collect())
+        }
+
+        fn part1(input: Self::Input) -> Result<Self::Answer1> {
+            Ok(input.len())
+        }
+
+        fn part2(input: Self::Input) -> Result<Self::Answer2> {
+            panic!("no part 2 for {} lines", input.len())
+        }
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_matches!(parse_args(args(&["--jobs", "3", "1", "17"])), Ok(options) => {
+            assert_eq!(vec![1, 17], options.days);
+            assert_eq!(3, options.jobs);
+        });
+        assert!(parse_args(args(&[])).unwrap().jobs > 0);
+        assert!(parse_args(args(&["--jobs", "0"])).is_err());
+        assert!(parse_args(args(&["one"])).is_err());
+    }
+
+    #[test]
+    fn test_run_jobs() {
+        let fragile: &'static dyn Day = &Fragile;
+        let day1 = crate::day(1).unwrap();
+        let job = |day: &'static dyn Day, input: &str, raw: &str, part| Job {
+            day,
+            input: input.to_string(),
+            raw: raw.to_string(),
+            part,
+        };
+        let jobs = vec![
+            job(fragile, "a", "x\ny\n", Part::One),
+            job(fragile, "a", "x\ny\n", Part::Two),
+            job(day1, "b", "1\n2\n", Part::One),
+            job(day1, "c", "1\nx\n", Part::One),
+        ];
+
+        let outcomes = run_jobs(&jobs, 3);
+        let answers: Vec<_> = outcomes.iter().map(|o| o.answer.clone()).collect();
+        // a panic says where it happened
+        assert_matches!(&answers[1], Err(Failure::Panic(msg)) => {
+            assert!(msg.starts_with("no part 2 for 2 lines at src/run_all.rs:"), "{}", msg);
+        });
+        assert_eq!(
+            vec![
+                Ok("2".to_string()),
+                answers[1].clone(),
+                Ok("1".to_string()),
+                Err(Failure::Error(
+                    "line 2, column 1: not a number: x".to_string()
+                )),
+            ],
+            answers
+        );
+        assert_eq!(
+            vec!["a", "a", "b", "c"],
+            outcomes
+                .iter()
+                .map(|o| o.input.as_str())
+                .collect::<Vec<_>>()
+        );
+
+        // the same however many workers there are
+        assert_eq!(
+            answers,
+            run_jobs(&jobs, 1)
+                .into_iter()
+                .map(|o| o.answer)
+                .collect::<Vec<_>>()
+        );
+        assert!(run_jobs(&[], 4).is_empty());
+    }
+
+    #[test]
+    fn test_run() {
+        let options = parse_args(args(&["-j", "4", "5", "12"])).unwrap();
+        let mut out = Vec::new();
+        assert!(run(&options, &mut out).unwrap());
+        let out = String::from_utf8(out).unwrap();
+        let rows: Vec<(&str, &str)> = out
+            .lines()
+            .skip(1)
+            .map(|line| {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                (fields[1], fields[2])
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                ("day5.txt", "1"),
+                ("day5.txt", "2"),
+                ("day5_test.txt", "1"),
+                ("day5_test.txt", "2"),
+                ("day12.txt", "1"),
+                ("day12.txt", "2"),
+                ("day12_test_1.txt", "1"),
+                ("day12_test_1.txt", "2"),
+                ("day12_test_2.txt", "1"),
+                ("day12_test_2.txt", "2"),
+                ("day12_test_3.txt", "1"),
+                ("day12_test_3.txt", "2"),
+            ],
+            rows
+        );
+        assert!(out.lines().skip(1).all(|line| line.ends_with("correct")));
+    }
+}
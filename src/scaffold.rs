@@ -0,0 +1,427 @@
+//! Starting a new day from a template
+
+use crate::error::{Error, Result};
+use std::fs;
+use std::io;
+use std::io::prelude::*;
+use std::path::{Path, PathBuf};
+
+pub const USAGE: &str = "usage: new-day [--root DIR] DAY";
+
+/// A day that parses each line and solves neither part, N being its number
+const TEMPLATE: &str = r#"use crate::error::{Error, Result};
+use crate::solution::Solution;
+
+// type aliases so that the template is easier
+type CalculationInput = String;
+type DayResult = u64;
+
+pub fn parse_line(input: String) -> Result<CalculationInput> {
+    Ok(input)
+}
+
+pub fn part1(input: impl Iterator<Item = CalculationInput>) -> Result<DayResult> {
+    Err(Error::invalid(format!(
+        "part 1 is not solved yet ({} lines)",
+        input.count()
+    )))
+}
+
+pub fn part2(input: impl Iterator<Item = CalculationInput>) -> Result<DayResult> {
+    Err(Error::invalid(format!(
+        "part 2 is not solved yet ({} lines)",
+        input.count()
+    )))
+}
+
+pub struct DayN;
+
+impl Solution for DayN {
+    const DAY: u8 = N;
+
+    type Input = Vec<CalculationInput>;
+    type Answer1 = DayResult;
+    type Answer2 = DayResult;
+
+    fn parse(raw: &str) -> Result<Self::Input> {
+        super::util::parse_lines(raw, parse_line)
+    }
+
+    fn validate(raw: &str) -> Vec<Error> {
+        super::util::check_lines(raw, parse_line)
+    }
+
+    fn part1(input: Self::Input) -> Result<Self::Answer1> {
+        part1(input.into_iter())
+    }
+
+    fn part2(input: Self::Input) -> Result<Self::Answer2> {
+        part2(input.into_iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::answers::verify;
+    use crate::solution::Part;
+
+    fn test_data() -> impl Iterator<Item = CalculationInput> {
+        super::super::util::parse_file("data/dayN_test.txt", parse_line).expect("test data")
+    }
+
+    fn puzzle_input() -> impl Iterator<Item = CalculationInput> {
+        super::super::util::parse_file("data/dayN.txt", parse_line).expect("puzzle input")
+    }
+
+    #[test]
+    #[ignore = "not solved yet"]
+    fn test_part1() {
+        let result = part1(test_data()).unwrap();
+
+        assert_eq!(0, result);
+
+        let result = part1(puzzle_input()).unwrap();
+
+        verify(N, Part::One, "dayN.txt", result);
+    }
+
+    #[test]
+    #[ignore = "not solved yet"]
+    fn test_part2() {
+        let result = part2(test_data()).unwrap();
+
+        assert_eq!(0, result);
+
+        let result = part2(puzzle_input()).unwrap();
+
+        verify(N, Part::Two, "dayN.txt", result);
+    }
+}
+"#;
+
+/// The source of a new day's module
+/// ```
+/// let source = aoc2021::scaffold::module(15);
+/// assert!(source.contains("pub struct Day15;"));
+/// assert!(source.contains("const DAY: u8 = 15;"));
+/// assert!(source.contains(r#""data/day15_test.txt""#));
+/// ```
+pub fn module(day: u8) -> String {
+    TEMPLATE
+        .replace("DayN", format!("Day{}", day).as_str())
+        .replace("dayN", format!("day{}", day).as_str())
+        .replace("= N;", format!("= {};", day).as_str())
+        .replace("verify(N,", format!("verify({},", day).as_str())
+}
+
+/// the number in a line such as `pub mod day12;` or `    &day12::Day12,`
+fn day_in(line: &str, prefix: &str, suffix: &str) -> Option<u8> {
+    line.strip_prefix(prefix)?
+        .strip_suffix(suffix)?
+        .split("::")
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Add a day's `pub mod` and `DAYS` entry to the source of lib.rs
+pub fn register(lib: &str, day: u8) -> Result<String> {
+    let module = format!("pub mod day{};", day);
+    let entry = format!("    &day{}::Day{},", day, day);
+    let mut lines: Vec<&str> = lib.lines().collect();
+
+    // by name, so day1 comes before day10
+    let name = format!("day{}", day);
+    let mods: Vec<(usize, String)> = (0..lines.len())
+        .filter_map(|i| day_in(lines[i], "pub mod day", ";").map(|n| (i, format!("day{}", n))))
+        .collect();
+    if mods.iter().any(|(_, m)| *m == name) {
+        return Err(Error::invalid(format!("day {} is already registered", day)));
+    }
+    let (last_mod, _) = *mods
+        .last()
+        .ok_or_else(|| Error::invalid("no day modules in lib.rs"))?;
+    let at = mods
+        .iter()
+        .find(|(_, m)| *m > name)
+        .map_or(last_mod + 1, |(i, _)| *i);
+    lines.insert(at, module.as_str());
+
+    let entries: Vec<(usize, u8)> = (0..lines.len())
+        .filter_map(|i| day_in(lines[i], "    &day", ",").map(|n| (i, n)))
+        .collect();
+    let (last_entry, _) = *entries
+        .last()
+        .ok_or_else(|| Error::invalid("no days in DAYS"))?;
+    let at = entries
+        .iter()
+        .find(|(_, n)| *n > day)
+        .map_or(last_entry + 1, |(i, _)| *i);
+    lines.insert(at, entry.as_str());
+
+    let mut registered = lines.join("\n");
+    registered.push('\n');
+    Ok(registered)
+}
+
+/// Write and register a new day in the crate at `root`, unless any of its files exist
+pub fn scaffold(root: &Path, day: u8) -> Result<Vec<PathBuf>> {
+    let io_error = |path: &Path| {
+        let context = path.display().to_string();
+        move |e| Error::io(context, e)
+    };
+
+    let lib_path = root.join("src").join("lib.rs");
+    let module_path = root.join("src").join(format!("day{}.rs", day));
+    let data = root.join("data");
+    let data_paths = [
+        data.join(format!("day{}.txt", day)),
+        data.join(format!("day{}_test.txt", day)),
+    ];
+
+    if let Some(existing) = std::iter::once(&module_path)
+        .chain(data_paths.iter())
+        .find(|p| p.exists())
+    {
+        return Err(Error::invalid(format!(
+            "{} already exists",
+            existing.display()
+        )));
+    }
+    let lib = fs::read_to_string(&lib_path).map_err(io_error(&lib_path))?;
+    let lib = register(lib.as_str(), day)?;
+
+    fs::write(&module_path, module(day)).map_err(io_error(&module_path))?;
+    fs::create_dir_all(&data).map_err(io_error(&data))?;
+    for path in data_paths.iter() {
+        fs::write(path, "").map_err(io_error(path))?;
+    }
+    fs::write(&lib_path, lib).map_err(io_error(&lib_path))?;
+
+    let mut written = vec![module_path, lib_path];
+    written.extend(data_paths);
+    Ok(written)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Options {
+    pub day: u8,
+    /// the crate's directory, holding src and data
+    pub root: PathBuf,
+}
+
+/// Parse the command line arguments (without the program name)
+pub fn parse_args(args: impl Iterator<Item = String>) -> std::result::Result<Options, String> {
+    let mut day = None;
+    let mut root = None;
+
+    let mut args = args;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-r" | "--root" => {
+                root = Some(PathBuf::from(args.next().ok_or("--root needs a value")?))
+            }
+            value if day.is_none() => {
+                day = match value.parse::<u8>() {
+                    Ok(n) if (1..=25).contains(&n) => Some(n),
+                    _ => return Err(format!("not a day: {}", value)),
+                };
+            }
+            value => return Err(format!("unexpected argument: {}", value)),
+        }
+    }
+
+    let day = day.ok_or("no day given")?;
+    let root = root.unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")));
+    Ok(Options { day, root })
+}
+
+/// Entry point for the new-day binary, returns the process exit code
+pub fn main() -> i32 {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(msg) => {
+            eprintln!("{}\n{}", msg, USAGE);
+            return 2;
+        }
+    };
+
+    match scaffold(&options.root, options.day) {
+        Ok(written) => {
+            let mut out = io::stdout();
+            for path in written {
+                let _ = writeln!(out, "wrote {}", path.display());
+            }
+            0
+        }
+        Err(e) => {
+            eprintln!("error: day {}: {}", options.day, e);
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const LIB: &str = "pub mod cli;
+pub mod day1;
+pub mod day14;
+pub mod day2;
+pub mod day9;
+pub mod error;
+
+pub static DAYS: &[&dyn Day] = &[
+    &day1::Day1,
+    &day2::Day2,
+    &day9::Day9,
+    &day14::Day14,
+];
+";
+
+    /// a crate of its own for each test, with just a lib.rs
+    fn scratch_crate(name: &str) -> PathBuf {
+        let root = crate::util::scratch_dir(format!("scaffold-{}", name).as_str());
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src").join("lib.rs"), LIB).unwrap();
+        root
+    }
+
+    #[test]
+    fn test_register() {
+        let lib = register(LIB, 15).unwrap();
+        assert!(lib.contains("pub mod day14;\npub mod day15;\npub mod day2;\n"));
+        assert!(lib.contains("    &day14::Day14,\n    &day15::Day15,\n];"));
+
+        let lib = register(&lib, 3).unwrap();
+        assert!(lib.contains("pub mod day2;\npub mod day3;\npub mod day9;\n"));
+        assert!(lib.contains("    &day2::Day2,\n    &day3::Day3,\n    &day9::Day9,\n"));
+
+        let lib = register(&lib, 10).unwrap();
+        assert!(lib.contains("pub mod day1;\npub mod day10;\npub mod day14;\n"));
+        assert!(lib.contains("    &day9::Day9,\n    &day10::Day10,\n    &day14::Day14,\n"));
+
+        let lib = register(&lib, 25).unwrap();
+        assert!(lib.contains("pub mod day2;\npub mod day25;\npub mod day3;\n"));
+        assert!(lib.contains("    &day15::Day15,\n    &day25::Day25,\n];"));
+
+        assert_matches!(register(&lib, 14), Err(Error::InvalidState(_)));
+        assert_matches!(register("", 14), Err(Error::InvalidState(_)));
+    }
+
+    #[test]
+    fn test_module() {
+        let source = module(21);
+        assert!(!source.contains("dayN") && !source.contains("DayN"));
+        assert!(source.contains("verify(21, Part::Two, \"day21.txt\", result);"));
+        assert!(source.contains("impl Solution for Day21 {"));
+    }
+
+    #[test]
+    fn test_scaffold() {
+        let root = scratch_crate("scaffold");
+        let written = scaffold(&root, 15).unwrap();
+        assert_eq!(4, written.len());
+        assert_eq!(
+            module(15),
+            fs::read_to_string(root.join("src/day15.rs")).unwrap()
+        );
+        assert_eq!("", fs::read_to_string(root.join("data/day15.txt")).unwrap());
+        assert_eq!(
+            "",
+            fs::read_to_string(root.join("data/day15_test.txt")).unwrap()
+        );
+        let lib = fs::read_to_string(root.join("src/lib.rs")).unwrap();
+        assert!(lib.contains("pub mod day15;"));
+
+        // a second go changes nothing
+        fs::write(root.join("src/day15.rs"), "// solved").unwrap();
+        assert_matches!(scaffold(&root, 15), Err(Error::InvalidState(_)));
+        assert_eq!(
+            "// solved",
+            fs::read_to_string(root.join("src/day15.rs")).unwrap()
+        );
+        assert_eq!(lib, fs::read_to_string(root.join("src/lib.rs")).unwrap());
+
+        // nor does an input that is already there
+        fs::write(root.join("data/day18.txt"), "mine").unwrap();
+        assert_matches!(scaffold(&root, 18), Err(Error::InvalidState(_)));
+        assert!(!root.join("src/day18.rs").exists());
+        assert!(!root.join("data/day18_test.txt").exists());
+        assert_eq!(lib, fs::read_to_string(root.join("src/lib.rs")).unwrap());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    /// what the template solves, until someone solves it
+    struct Unsolved;
+
+    impl crate::solution::Solution for Unsolved {
+        const DAY: u8 = 15;
+
+        type Input = Vec<String>;
+        type Answer1 = u64;
+        type Answer2 = u64;
+
+        fn parse(raw: &str) -> Result<Self::Input> {
+            crate::util::parse_lines(raw, Ok)
+        }
+
+        fn validate(raw: &str) -> Vec<Error> {
+            crate::util::check_lines(raw, Ok)
+        }
+
+        fn part1(_: Self::Input) -> Result<Self::Answer1> {
+            Err(Error::invalid("part 1 is not solved yet"))
+        }
+
+        fn part2(_: Self::Input) -> Result<Self::Answer2> {
+            Err(Error::invalid("part 2 is not solved yet"))
+        }
+    }
+
+    #[test]
+    fn test_scaffolded_day_sweeps() {
+        // the tests covering every day leave a new one alone
+        use crate::solution::{Day, Part};
+        use crate::util::{check_examples_solve, check_inputs_validate, DataDir};
+
+        let root = scratch_crate("sweeps");
+        scaffold(&root, 15).unwrap();
+        let data = DataDir::new(root.join("data"));
+        let days: &[&dyn Day] = &[&Unsolved];
+
+        check_examples_solve(days, &data);
+        check_inputs_validate(days, &data);
+        crate::generate::check_generated_inputs_solve(days);
+
+        // nor once the example is pasted in, with no answers for it yet
+        fs::write(root.join("data/day15_test.txt"), "1\n2\n3\n").unwrap();
+        assert!(Unsolved.run("1\n2\n3\n", Part::One).is_err());
+        check_examples_solve(days, &data);
+        check_inputs_validate(days, &data);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let args = |values: &[&str]| {
+            values
+                .iter()
+                .map(|s| s.to_string())
+                .collect::<Vec<String>>()
+                .into_iter()
+        };
+        assert_matches!(parse_args(args(&["--root", "/tmp/x", "18"])), Ok(options) => {
+            assert_eq!(18, options.day);
+            assert_eq!(PathBuf::from("/tmp/x"), options.root);
+        });
+        assert!(parse_args(args(&["26"])).is_err());
+        assert!(parse_args(args(&[])).is_err());
+    }
+}
@@ -0,0 +1,176 @@
+use crate::error::{Error, Result};
+use crate::report::Report;
+use std::any::Any;
+use std::fmt::Display;
+
+/// Which half of a day's puzzle to solve
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub fn number(&self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+/// Common interface for each day: parse the raw puzzle text into the
+/// day's typed input, then solve either part from that input.
+///
+/// ```
+/// use aoc2021::day1::Day1;
+/// use aoc2021::solution::Solution;
+///
+/// let input = Day1::parse("199\n200\n208\n210\n200\n207\n240\n269\n260\n263").unwrap();
+/// assert_eq!(7, Day1::part1(input).unwrap());
+/// ```
+pub trait Solution {
+    const DAY: u8;
+
+    type Input: Send + 'static;
+    type Answer1: Display;
+    type Answer2: Display;
+
+    fn parse(raw: &str) -> Result<Self::Input>;
+
+    fn part1(input: Self::Input) -> Result<Self::Answer1>;
+
+    fn part2(input: Self::Input) -> Result<Self::Answer2>;
+
+    /// Every problem with the shape of an input, without solving it. By
+    /// default this is just the first error from `parse`.
+    fn validate(raw: &str) -> Vec<Error> {
+        Self::parse(raw).err().into_iter().collect()
+    }
+
+    /// part 1 along with any named intermediate values
+    fn report1(input: Self::Input) -> Result<Report> {
+        Self::part1(input).map(Report::new)
+    }
+
+    /// part 2 along with any named intermediate values
+    fn report2(input: Self::Input) -> Result<Report> {
+        Self::part2(input).map(Report::new)
+    }
+}
+
+/// A day's parsed input, with the concrete type erased
+pub type Parsed = Box<dyn Any + Send>;
+
+/// Object safe version of `Solution`, so that days with different input
+/// and answer types can be kept together in the registry.
+pub trait Day: Sync {
+    fn number(&self) -> u8;
+
+    fn parse(&self, raw: &str) -> Result<Parsed>;
+
+    fn validate(&self, raw: &str) -> Vec<Error>;
+
+    /// the input must have come from this day's `parse`
+    fn solve(&self, input: Parsed, part: Part) -> Result<String>;
+
+    /// like `solve`, keeping the intermediate values
+    fn report(&self, input: Parsed, part: Part) -> Result<Report>;
+
+    fn run(&self, raw: &str, part: Part) -> Result<String> {
+        self.parse(raw).and_then(|input| self.solve(input, part))
+    }
+}
+
+impl<S> Day for S
+where
+    S: Solution + Sync,
+{
+    fn number(&self) -> u8 {
+        S::DAY
+    }
+
+    fn parse(&self, raw: &str) -> Result<Parsed> {
+        <S as Solution>::parse(raw).map(|input| Box::new(input) as Parsed)
+    }
+
+    fn validate(&self, raw: &str) -> Vec<Error> {
+        <S as Solution>::validate(raw)
+    }
+
+    fn solve(&self, input: Parsed, part: Part) -> Result<String> {
+        let input = *input
+            .downcast::<S::Input>()
+            .expect("input was not parsed by this day");
+        match part {
+            Part::One => S::part1(input).map(|a| a.to_string()),
+            Part::Two => S::part2(input).map(|a| a.to_string()),
+        }
+    }
+
+    fn report(&self, input: Parsed, part: Part) -> Result<Report> {
+        let input = *input
+            .downcast::<S::Input>()
+            .expect("input was not parsed by this day");
+        match part {
+            Part::One => S::report1(input),
+            Part::Two => S::report2(input),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_registry() {
+        // in order, once each, and room for days still to come
+        let numbers: Vec<u8> = crate::DAYS.iter().map(|d| d.number()).collect();
+        assert!(numbers.windows(2).all(|w| w[0] < w[1]), "{:?}", numbers);
+        assert!(
+            numbers.iter().all(|n| (1..=25).contains(n)),
+            "{:?}",
+            numbers
+        );
+        for n in [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 16, 17] {
+            assert!(numbers.contains(&n), "day {} is missing", n);
+        }
+
+        assert!(crate::day(0).is_none());
+        assert!(crate::day(26).is_none());
+        assert_eq!(Some(5), crate::day(5).map(|d| d.number()));
+    }
+
+    #[test]
+    fn test_run() {
+        let day = crate::day(2).expect("day 2 is registered");
+        let raw = "forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2\n";
+
+        assert_eq!("150", day.run(raw, Part::One).unwrap());
+        assert_eq!("900", day.run(raw, Part::Two).unwrap());
+
+        assert_matches!(
+            day.run("forward 5\nsideways 5", Part::One),
+            Err(crate::error::Error::Parse { line: 2, .. })
+        );
+    }
+
+    #[test]
+    fn test_report() {
+        let day = crate::day(7).expect("day 7 is registered");
+        let input = day.parse("16,1,2,0,4,2,7,1,2,14").unwrap();
+        let report = day.report(input, Part::One).unwrap();
+        assert_eq!("37", report.answer);
+        assert_eq!(r#"{"position":2,"fuel":37}"#, report.fields().to_string());
+
+        // days without intermediates just have the answer
+        let day = crate::day(1).expect("day 1 is registered");
+        let report = day
+            .report(day.parse("1\n2\n3").unwrap(), Part::One)
+            .unwrap();
+        assert_eq!("2", report.answer);
+        assert!(report.fields.is_empty());
+    }
+}
@@ -0,0 +1,172 @@
+//! Solvers that take their input a line at a time, for inputs that arrive
+//! as they are measured and may never end.
+
+use crate::error::{Error, Result};
+use std::fmt::Display;
+use std::io::BufRead;
+
+/// A solver that is pushed one line at a time and knows its answer for the
+/// lines so far at any point
+/// ```
+/// use aoc2021::day1::Increases;
+/// use aoc2021::stream::Streaming;
+///
+/// let mut increases = Increases::default();
+/// for line in ["199", "200", "208", "200"] {
+///     increases.push(line).unwrap();
+/// }
+/// assert_eq!(2, increases.answer());
+/// ```
+pub trait Streaming {
+    type Answer: Display;
+
+    /// Take the next line. A line that can't be used is an error (without a
+    /// line number), and leaves the answer as it was.
+    fn push(&mut self, line: &str) -> Result<()>;
+
+    /// the answer for every line taken so far
+    fn answer(&self) -> Self::Answer;
+}
+
+/// Push each line of `reader` as soon as it can be read, skipping blank
+/// lines, and call `progress` with the answer after each one. Returns the
+/// answer once the reader runs out.
+/// ```
+/// use aoc2021::day10::SyntaxScore;
+/// use aoc2021::stream;
+///
+/// let mut scores = Vec::new();
+/// let raw = "{([(<{}[<>[]}>{[]{[(<()>\n\n[[<[([]))<([[{}[[()]]]\n";
+/// let total = stream::feed(&mut SyntaxScore::default(), raw.as_bytes(), |s| scores.push(s));
+/// assert_eq!(1197 + 3, total.unwrap());
+/// assert_eq!(vec![1197, 1200], scores);
+/// ```
+pub fn feed<S: Streaming>(
+    solver: &mut S,
+    reader: impl BufRead,
+    mut progress: impl FnMut(S::Answer),
+) -> Result<S::Answer> {
+    for (i, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| Error::io("input", e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        solver.push(line.as_str()).map_err(|e| e.at_line(i + 1))?;
+        progress(solver.answer());
+    }
+    Ok(solver.answer())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{day1, day10, day2, day5};
+    use std::fs;
+
+    /// the streamed answer to a whole file
+    fn streamed<S: Streaming>(mut solver: S, path: &str) -> String {
+        let raw = fs::read_to_string(path).unwrap();
+        feed(&mut solver, raw.as_bytes(), |_| ())
+            .unwrap()
+            .to_string()
+    }
+
+    /// the answer from solving a whole file at once
+    fn solved(day: u8, part: crate::solution::Part, path: &str) -> String {
+        let raw = fs::read_to_string(path).unwrap();
+        crate::day(day).unwrap().run(&raw, part).unwrap()
+    }
+
+    #[test]
+    fn test_same_answers() {
+        use crate::solution::Part::{One, Two};
+
+        for path in ["data/day1_test.txt", "data/day1.txt"] {
+            assert_eq!(
+                solved(1, One, path),
+                streamed(day1::Increases::default(), path)
+            );
+        }
+        for path in ["data/day2_test.txt", "data/day2.txt"] {
+            assert_eq!(solved(2, One, path), streamed(day2::Navigator::new(), path));
+            assert_eq!(
+                solved(2, Two, path),
+                streamed(day2::Navigator::with_aim(), path)
+            );
+        }
+        for path in ["data/day5_test.txt", "data/day5.txt"] {
+            assert_eq!(
+                solved(5, One, path),
+                streamed(day5::Overlaps::new(false), path)
+            );
+            assert_eq!(
+                solved(5, Two, path),
+                streamed(day5::Overlaps::new(true), path)
+            );
+        }
+        for path in ["data/day10_test.txt", "data/day10.txt"] {
+            assert_eq!(
+                solved(10, One, path),
+                streamed(day10::SyntaxScore::default(), path)
+            );
+        }
+    }
+
+    #[test]
+    fn test_progress() {
+        let mut depths = Vec::new();
+        let raw = "forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2\n";
+        let mut navigator = day2::Navigator::new();
+        let answer = feed(&mut navigator, raw.as_bytes(), |_| ()).unwrap();
+        assert_eq!(150, answer);
+        assert_eq!(
+            (10, 15),
+            (navigator.submarine.depth, navigator.submarine.horiz)
+        );
+
+        let mut navigator = day2::Navigator::new();
+        feed(&mut navigator, raw.as_bytes(), |a| depths.push(a)).unwrap();
+        assert_eq!(vec![0, 25, 65, 26, 130, 150], depths);
+    }
+
+    #[test]
+    fn test_no_scripts() {
+        // streaming takes a plain course a line at a time, not a script
+        for (raw, bad) in [
+            ("forward 5\n# then dive\ndown 5\n", 2),
+            ("forward 5\nrepeat 2 { down 5 }\n", 2),
+            ("forward 5\nback 2\nmacro dive {\n", 3),
+        ] {
+            let mut navigator = day2::Navigator::new();
+            assert_matches!(
+                feed(&mut navigator, raw.as_bytes(), |_| ()),
+                Err(Error::Parse { line, .. }) if line == bad
+            );
+        }
+    }
+
+    #[test]
+    fn test_bad_line() {
+        let mut overlaps = day5::Overlaps::new(false);
+        let raw = "0,9 -> 5,9\n\n0,9 -> 2,9\nnothing\n";
+        assert_matches!(
+            feed(&mut overlaps, raw.as_bytes(), |_| ()),
+            Err(Error::Parse { line: 4, .. })
+        );
+        // what came before the bad line still counts, and the stream can go on
+        assert_eq!(3, overlaps.answer());
+        overlaps.push("3,9 -> 3,9").unwrap();
+        assert_eq!(4, overlaps.answer());
+        overlaps.push("3,8 -> 3,10").unwrap();
+        assert_eq!(4, overlaps.answer());
+        // diagonals are only counted when asked for
+        overlaps.push("0,0 -> 9,9").unwrap();
+        assert_eq!(4, overlaps.answer());
+
+        let mut score = day10::SyntaxScore::default();
+        score.push("(]").unwrap();
+        assert_matches!(score.push("(]x"), Err(Error::Parse { column: 3, .. }));
+        assert_eq!(57, score.answer());
+    }
+}
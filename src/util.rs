@@ -1,70 +1,280 @@
-use std::collections::VecDeque;
+use crate::error::{Error, Result};
 use std::fs;
 use std::io;
 use std::io::prelude::*;
 use std::path;
 
-/// ```should_panic
+mod data;
+mod grid;
+#[cfg(test)]
+mod scratch;
+mod window;
+#[cfg(test)]
+pub use data::{check_examples_solve, check_inputs_validate};
+pub use data::{DataDir, Kind, Variant, DATA_DIR_VAR};
+pub use grid::Grid;
+#[cfg(test)]
+pub use scratch::scratch_dir;
+pub use window::{Aggregate, Fold, Max, Min, SlidingWindows, Sum, Windows};
+
+/// ```
 /// use aoc2021::util;
-/// let _ = util::read_file("/not/exist.txt");
+/// assert!(util::read_file("/not/exist.txt").is_err());
 ///```
-pub fn read_file(file_path: &str) -> impl Iterator<Item = String> {
+/// The whole file is read before this returns, so a failed read is
+/// reported here rather than part way through
+pub fn read_file(file_path: &str) -> Result<impl Iterator<Item = String>> {
+    let lines = open_lines(file_path)?.collect::<Result<Vec<String>>>()?;
+    Ok(lines.into_iter())
+}
+
+fn open_lines(file_path: &str) -> Result<impl Iterator<Item = Result<String>>> {
     let path = path::Path::new(file_path);
 
-    let file = fs::File::open(path).expect("file should exist");
+    let file = fs::File::open(path).map_err(|e| Error::io(file_path, e))?;
 
-    io::BufReader::new(file)
+    Ok(read_lines(io::BufReader::new(file), file_path))
+}
+
+/// Read each line from any source, such as a file, stdin or a byte slice,
+/// only as it is needed
+/// ```
+/// use aoc2021::util;
+///
+/// let lines: Vec<String> = util::read_strings_from_reader("a\nb\n".as_bytes())
+///     .collect::<Result<_, _>>()
+///     .unwrap();
+/// assert_eq!(vec!["a", "b"], lines);
+/// ```
+pub fn read_strings_from_reader(reader: impl BufRead) -> impl Iterator<Item = Result<String>> {
+    read_lines(reader, "input")
+}
+
+fn read_lines(reader: impl BufRead, context: &str) -> impl Iterator<Item = Result<String>> {
+    let context = context.to_string();
+    reader
         .lines()
-        .map(|r| r.expect("could not read lines"))
+        .map(move |line| line.map_err(|e| Error::io(context.as_str(), e)))
 }
 
-pub fn read_data(day: u8, for_test: bool) -> impl Iterator<Item = String> {
-    let file_path = if for_test {
-        format!("data/day{}_test.txt", day)
+/// Read a day's puzzle input or its example, wherever `DataDir::locate` finds them
+pub fn read_data(day: u8, for_test: bool) -> Result<impl Iterator<Item = String>> {
+    let kind = if for_test {
+        Kind::Example(None)
     } else {
-        format!("data/day{}.txt", day)
+        Kind::Puzzle
     };
-    read_file(file_path.as_str())
+    DataDir::locate().read(day, kind)
+}
+
+/// Parse each non-empty line, reporting the line number of the first failure
+fn parse_numbered<T>(
+    lines: impl Iterator<Item = Result<String>>,
+    parse_line: impl Fn(String) -> Result<T>,
+) -> Result<Vec<T>> {
+    let mut parsed = Vec::new();
+    for (i, line) in lines.enumerate() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            parsed.push(parse_line(line).map_err(|e| e.at_line(i + 1))?);
+        }
+    }
+    Ok(parsed)
 }
 
 pub fn parse_file<T>(
     file_path: &str,
-    parse_line: impl Fn(String) -> Result<T, String>,
-) -> impl Iterator<Item = T> {
-    read_file(file_path)
-        .map(parse_line)
-        .map(|r| r.expect("each input line in was not converted successully"))
+    parse_line: impl Fn(String) -> Result<T>,
+) -> Result<impl Iterator<Item = T>> {
+    Ok(parse_numbered(open_lines(file_path)?, parse_line)?.into_iter())
 }
 
-pub fn read_strings_from_file(file_path: &str) -> impl Iterator<Item = String> {
-    read_file(file_path)
+pub fn parse_reader<T>(
+    reader: impl BufRead,
+    parse_line: impl Fn(String) -> Result<T>,
+) -> Result<impl Iterator<Item = T>> {
+    Ok(parse_numbered(read_strings_from_reader(reader), parse_line)?.into_iter())
 }
 
-pub fn read_ints_from_file(file_path: &str) -> impl Iterator<Item = i32> {
+pub fn read_strings_from_file(file_path: &str) -> Result<impl Iterator<Item = String>> {
     read_file(file_path)
-        .map(|v| v.parse::<i32>())
-        .filter_map(Result::ok)
 }
 
-pub fn read_string_int_from_file(file_path: &str) -> impl Iterator<Item = (String, i32)> {
-    read_file(file_path).map(|v| {
-        let mut i = v.split(' ');
-        (
-            i.next().unwrap().to_string(),
-            i.next().unwrap().parse::<i32>().unwrap(),
+pub fn read_ints_from_file(file_path: &str) -> Result<impl Iterator<Item = i32>> {
+    parse_file(file_path, parse_int)
+}
+
+/// ```
+/// use aoc2021::util;
+///
+/// let ints: Vec<i32> = util::read_ints_from_reader("199\n200\n-3".as_bytes())
+///     .unwrap()
+///     .collect();
+/// assert_eq!(vec![199, 200, -3], ints);
+/// ```
+pub fn read_ints_from_reader(reader: impl BufRead) -> Result<impl Iterator<Item = i32>> {
+    parse_reader(reader, parse_int)
+}
+
+pub fn read_string_int_from_file(file_path: &str) -> Result<impl Iterator<Item = (String, i32)>> {
+    parse_file(file_path, parse_string_int)
+}
+
+pub fn read_string_int_from_reader(
+    reader: impl BufRead,
+) -> Result<impl Iterator<Item = (String, i32)>> {
+    parse_reader(reader, parse_string_int)
+}
+
+pub fn read_int_list_from_file(file_path: &str) -> Result<impl Iterator<Item = Vec<u8>>> {
+    parse_file(file_path, parse_int_list)
+}
+
+pub fn read_int_list_from_reader(reader: impl BufRead) -> Result<impl Iterator<Item = Vec<u8>>> {
+    parse_reader(reader, parse_int_list)
+}
+
+/// Read a grid of single digits, one row per line
+pub fn read_digit_grid_from_file(file_path: &str) -> Result<Grid<u8>> {
+    let raw = fs::read_to_string(file_path).map_err(|e| Error::io(file_path, e))?;
+    Grid::parse_digits(raw.as_str())
+}
+
+/// Parse each non-empty line of an in-memory input
+/// ```
+/// use aoc2021::util;
+///
+/// let parsed = util::parse_lines("1\n2\n\n3", util::parse_int);
+/// assert_eq!(vec![1, 2, 3], parsed.unwrap());
+///
+/// let e = util::parse_lines("1\n2\nthree", util::parse_int).unwrap_err();
+/// assert_eq!("line 3, column 1: not a number: three", e.to_string());
+/// ```
+pub fn parse_lines<T>(input: &str, parse_line: impl Fn(String) -> Result<T>) -> Result<Vec<T>> {
+    parse_numbered(input.lines().map(|line| Ok(line.to_string())), parse_line)
+}
+
+/// The problem reported for an input with nothing in it
+pub fn empty_input() -> Error {
+    Error::parse(0, 0, "input is empty")
+}
+
+/// Check each non-empty line, reporting every failure rather than just the first
+/// ```
+/// use aoc2021::util;
+///
+/// let problems = util::check_lines("1\nx\n3\ny", util::parse_int);
+/// let lines: Vec<String> = problems.iter().map(|e| e.to_string()).collect();
+/// assert_eq!(
+///     vec!["line 2, column 1: not a number: x", "line 4, column 1: not a number: y"],
+///     lines
+/// );
+/// ```
+pub fn check_lines<T>(input: &str, parse_line: impl Fn(String) -> Result<T>) -> Vec<Error> {
+    let mut problems = Vec::new();
+    let mut empty = true;
+    for (i, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        empty = false;
+        if let Err(e) = parse_line(line.to_string()) {
+            problems.push(e.at_line(i + 1));
+        }
+    }
+    if empty {
+        problems.push(empty_input());
+    }
+    problems
+}
+
+/// Like `check_lines`, for inputs that should be a single line
+pub fn check_single_line<T>(input: &str, parse_line: impl Fn(String) -> Result<T>) -> Vec<Error> {
+    let mut problems = check_lines(input, parse_line);
+    problems.extend(
+        input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .skip(1)
+            .map(|(i, _)| Error::parse(i + 1, 0, "there should be one line")),
+    );
+    problems.sort_by_key(|e| match e {
+        Error::Parse { line, .. } => *line,
+        _ => 0,
+    });
+    problems
+}
+
+/// Check that the input is a rectangle of characters accepted by `valid`
+pub fn check_grid(input: &str, valid: impl Fn(char) -> bool) -> Vec<Error> {
+    let mut problems = Vec::new();
+    let mut width = None;
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        for (j, c) in line.chars().enumerate() {
+            if !valid(c) {
+                problems.push(Error::parse(i + 1, j + 1, format!("unexpected: {}", c)));
+            }
+        }
+        let found = line.chars().count();
+        match width {
+            None => width = Some(found),
+            Some(width) if width != found => problems.push(Error::parse(
+                i + 1,
+                0,
+                format!("expected {} columns, found {}", width, found),
+            )),
+            _ => (),
+        }
+    }
+    if width.is_none() {
+        problems.push(empty_input());
+    }
+    problems
+}
+
+/// Parse a line such as "-42"
+pub fn parse_int(input: String) -> Result<i32> {
+    let trimmed = input.trim();
+    trimmed.parse::<i32>().map_err(|_| {
+        Error::parse_line(
+            input.len() - input.trim_start().len() + 1,
+            format!("not a number: {}", trimmed),
         )
     })
 }
 
-pub fn read_int_list_from_file(file_path: &str) -> impl Iterator<Item = Vec<u8>> {
-    // const RADIX: u32 = 10;
-    // c.to_digit(RADIX).unwrap();
+/// Parse a line such as "forward 5"
+pub fn parse_string_int(input: String) -> Result<(String, i32)> {
+    let mut i = input.trim().split(' ');
+    match (i.next(), i.next(), i.next()) {
+        (Some(s), Some(v), None) => match v.parse::<i32>() {
+            Ok(v) => Ok((s.to_string(), v)),
+            Err(_) => Err(Error::parse_line(
+                input.find(v).unwrap_or(0) + 1,
+                format!("not a number: {}", v),
+            )),
+        },
+        _ => Err(Error::parse_line(0, format!("error parsing: {}", input))),
+    }
+}
 
-    read_file(file_path).map(|s| {
-        s.chars()
-            .map(|c| c.to_string().parse::<u8>().unwrap())
-            .collect()
-    })
+/// Parse a line of single digits such as "01101"
+pub fn parse_int_list(input: String) -> Result<Vec<u8>> {
+    input
+        .trim()
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            c.to_digit(10)
+                .map(|d| d as u8)
+                .ok_or_else(|| Error::parse_line(i + 1, format!("not a digit: {}", c)))
+        })
+        .collect()
 }
 
 /*
@@ -146,97 +356,112 @@ impl<I> Iterator for IntFileReader<I>
 }
 */
 
-pub struct Triple<T> {
-    pub a: T,
-    pub b: T,
-    pub c: T,
-    pub sum: T,
-}
-
-impl Triple<i32> {
-    pub fn new(source: Vec<i32>) -> Self {
-        assert!(source.len() == 3);
-        Triple {
-            a: source[0],
-            b: source[1],
-            c: source[2],
-            sum: source[0] + source[1] + source[2],
-        }
-    }
-}
-
-pub struct TripleIter<I>
-where
-    I: Iterator<Item = i32>,
-{
-    backlog: VecDeque<i32>,
-    iter: I,
-}
-
-impl<I> TripleIter<I>
-where
-    I: Iterator<Item = i32>,
-{
-    pub fn new(iter: I) -> TripleIter<I> {
-        TripleIter {
-            backlog: VecDeque::with_capacity(3),
-            iter,
-        }
-    }
-}
-
-impl<I> Iterator for TripleIter<I>
-where
-    I: Iterator<Item = i32>,
-{
-    // i32
-    type Item = I::Item;
-    fn next(&mut self) -> Option<i32> {
-        // while let Some(entry) = self.iter.next() {
-        for entry in &mut self.iter {
-            self.backlog.push_front(entry);
-
-            if self.backlog.len() == 3 {
-                let value = Triple::new(vec![self.backlog[0], self.backlog[1], self.backlog[2]]);
-                self.backlog.pop_back();
-                return Some(value.sum);
-            }
-        }
-        // not enough values to continue, so lets clean up
-        self.backlog.clear();
-        None
-    }
-}
-
 #[cfg(test)]
 mod tests {
 
     use super::*;
 
     #[test]
-    fn test_add_triples() {
-        let records = vec![1, 2, 3, 4];
-        // what's up with this copied business ?
-        let mut triple = TripleIter::new(records.iter().copied());
+    fn test_int_list() {
+        let mut records = read_int_list_from_file("data/day3.txt").expect("day 3 input");
 
-        assert_matches!(triple.next(), Some(value) => {
-            assert_eq!(6, value)
-        });
+        match records.next() {
+            Some(r) => assert_eq!(vec![1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1], r),
+            None => unreachable!(),
+        }
+    }
 
-        assert_matches!(triple.next(), Some(value) => {
-            assert_eq!(9, value)
+    #[test]
+    fn test_readers() {
+        let mut records = read_int_list_from_reader("0101\n1100\n".as_bytes()).unwrap();
+        assert_eq!(Some(vec![0, 1, 0, 1]), records.next());
+        assert_eq!(Some(vec![1, 1, 0, 0]), records.next());
+        assert_eq!(None, records.next());
+
+        let mut records = read_string_int_from_reader(io::Cursor::new("up 3\n\ndown 4")).unwrap();
+        assert_eq!(Some(("up".to_string(), 3)), records.next());
+        assert_eq!(Some(("down".to_string(), 4)), records.next());
+
+        // the same lines through a file and an in-memory reader
+        let from_file: Vec<i32> = read_ints_from_file("data/day1_test.txt").unwrap().collect();
+        let raw = fs::read("data/day1_test.txt").unwrap();
+        let from_reader: Vec<i32> = read_ints_from_reader(raw.as_slice()).unwrap().collect();
+        assert_eq!(from_file, from_reader);
+
+        assert_matches!(
+            read_ints_from_reader("1\n2\nx".as_bytes()).err(),
+            Some(Error::Parse { line: 3, .. })
+        );
+        assert_matches!(
+            read_ints_from_reader(&[b'1', b'\n', 0xff, 0xfe][..]).err(),
+            Some(Error::Io { .. })
+        );
+
+        // lines are read as they are asked for, so a bad line doesn't stop
+        // the ones before it
+        let mut lines = read_strings_from_reader(&[b'a', b'\n', 0xff, 0xfe][..]);
+        assert_eq!("a", lines.next().unwrap().unwrap());
+        assert_matches!(lines.next(), Some(Err(Error::Io { .. })));
+        // nor does an endless one need to end
+        let mut lines = read_strings_from_reader(io::BufReader::new(io::repeat(b'\n')));
+        assert_eq!("", lines.nth(1000).unwrap().unwrap());
+    }
+
+    #[test]
+    fn test_checks() {
+        assert_matches!(check_lines("\n \n", parse_int).as_slice(), [e] => {
+            assert_eq!("input is empty", e.to_string());
         });
+        assert!(check_lines("1\n\n2\n", parse_int).is_empty());
+
+        let lines = |problems: Vec<Error>| -> Vec<usize> {
+            problems
+                .iter()
+                .map(|e| match e {
+                    Error::Parse { line, .. } => *line,
+                    _ => 0,
+                })
+                .collect()
+        };
+        assert!(check_single_line("1,2,3\n", parse_int_list_csv).is_empty());
+        assert_eq!(
+            vec![2, 2, 4],
+            lines(check_single_line("1\nx\n\n4", parse_int))
+        );
+        assert_eq!(vec![0], lines(check_single_line("", parse_int)));
+
+        assert!(check_grid("12\n34\n", |c| c.is_ascii_digit()).is_empty());
+        assert_eq!(
+            vec![1, 2, 3],
+            lines(check_grid("1x\n345\n6", |c| c.is_ascii_digit()))
+        );
+    }
 
-        assert_matches!(triple.next(), None);
+    fn parse_int_list_csv(line: String) -> Result<Vec<i32>> {
+        line.split(',').map(|v| parse_int(v.to_string())).collect()
     }
 
     #[test]
-    fn test_int_list() {
-        let mut records = read_int_list_from_file("data/day3.txt");
-
-        match records.next() {
-            Some(r) => assert_eq!(vec![1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1], r),
-            None => unreachable!(),
-        }
+    fn test_parse_errors() {
+        assert_matches!(read_file("data/none.txt").err(), Some(Error::Io { .. }));
+
+        assert_matches!(
+            parse_int_list("0120x1".to_string()),
+            Err(Error::Parse { column: 5, .. })
+        );
+        assert_matches!(
+            parse_string_int("up x".to_string()),
+            Err(Error::Parse { column: 4, .. })
+        );
+        assert_matches!(parse_string_int("up".to_string()), Err(Error::Parse { .. }));
+
+        assert_matches!(
+            parse_lines("1\n\n2\n 3x", parse_int),
+            Err(Error::Parse {
+                line: 4,
+                column: 2,
+                ..
+            })
+        );
     }
 }
@@ -0,0 +1,246 @@
+use crate::error::{Error, Result};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// environment variable that overrides where puzzle inputs are found
+pub const DATA_DIR_VAR: &str = "AOC_DATA_DIR";
+
+/// What kind of input a data file holds
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Kind {
+    /// dayN.txt
+    Puzzle,
+    /// dayN_test.txt, or dayN_test_K.txt when a day has several examples
+    Example(Option<u8>),
+}
+
+/// One input file for a day
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Variant {
+    pub day: u8,
+    pub kind: Kind,
+    pub path: PathBuf,
+}
+
+impl Variant {
+    /// the file name, e.g. day12_test_1.txt
+    pub fn name(&self) -> String {
+        file_name(self.day, self.kind)
+    }
+}
+
+fn file_name(day: u8, kind: Kind) -> String {
+    match kind {
+        Kind::Puzzle => format!("day{}.txt", day),
+        Kind::Example(None) => format!("day{}_test.txt", day),
+        Kind::Example(Some(case)) => format!("day{}_test_{}.txt", day, case),
+    }
+}
+
+/// the inverse of file_name, for files that belong to `day`
+fn parse_name(day: u8, name: &str) -> Option<Kind> {
+    let rest = name
+        .strip_prefix(format!("day{}", day).as_str())?
+        .strip_suffix(".txt")?;
+    if rest.is_empty() {
+        return Some(Kind::Puzzle);
+    }
+    let rest = rest.strip_prefix("_test")?;
+    if rest.is_empty() {
+        return Some(Kind::Example(None));
+    }
+    rest.strip_prefix('_')?
+        .parse::<u8>()
+        .ok()
+        .map(|case| Kind::Example(Some(case)))
+}
+
+/// The directory holding the puzzle inputs
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DataDir {
+    root: PathBuf,
+}
+
+impl DataDir {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        DataDir { root: root.into() }
+    }
+
+    /// Use $AOC_DATA_DIR if it is set, then ./data if it exists, and
+    /// finally the data directory checked in next to this crate.
+    pub fn locate() -> Self {
+        if let Some(root) = env::var_os(DATA_DIR_VAR) {
+            return DataDir::new(root);
+        }
+        let cwd = PathBuf::from("data");
+        if cwd.is_dir() {
+            DataDir::new(cwd)
+        } else {
+            DataDir::new(Path::new(env!("CARGO_MANIFEST_DIR")).join("data"))
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn path(&self, day: u8, kind: Kind) -> PathBuf {
+        self.root.join(file_name(day, kind))
+    }
+
+    pub fn puzzle(&self, day: u8) -> PathBuf {
+        self.path(day, Kind::Puzzle)
+    }
+
+    pub fn example(&self, day: u8, case: Option<u8>) -> PathBuf {
+        self.path(day, Kind::Example(case))
+    }
+
+    /// Every input file present for a day, the puzzle first then the
+    /// examples in order.
+    /// ```
+    /// use aoc2021::util::{DataDir, Kind};
+    ///
+    /// let kinds: Vec<Kind> = DataDir::locate()
+    ///     .variants(12)
+    ///     .unwrap()
+    ///     .iter()
+    ///     .map(|v| v.kind)
+    ///     .collect();
+    /// assert_eq!(
+    ///     vec![Kind::Puzzle, Kind::Example(Some(1)), Kind::Example(Some(2)), Kind::Example(Some(3))],
+    ///     kinds
+    /// );
+    /// ```
+    pub fn variants(&self, day: u8) -> Result<Vec<Variant>> {
+        let context = self.root.display().to_string();
+        let mut found = Vec::new();
+
+        for entry in fs::read_dir(&self.root).map_err(|e| Error::io(context.as_str(), e))? {
+            let entry = entry.map_err(|e| Error::io(context.as_str(), e))?;
+            let name = entry.file_name();
+            if let Some(kind) = name.to_str().and_then(|n| parse_name(day, n)) {
+                found.push(Variant {
+                    day,
+                    kind,
+                    path: entry.path(),
+                });
+            }
+        }
+        found.sort_by_key(|v| v.kind);
+        Ok(found)
+    }
+
+    /// just the examples for a day
+    pub fn examples(&self, day: u8) -> Result<Vec<Variant>> {
+        Ok(self
+            .variants(day)?
+            .into_iter()
+            .filter(|v| v.kind != Kind::Puzzle)
+            .collect())
+    }
+
+    pub fn read(&self, day: u8, kind: Kind) -> Result<impl Iterator<Item = String>> {
+        let path = self.path(day, kind);
+        super::read_file(path.to_string_lossy().as_ref())
+    }
+
+    pub fn read_to_string(&self, day: u8, kind: Kind) -> Result<String> {
+        let path = self.path(day, kind);
+        fs::read_to_string(&path).map_err(|e| Error::io(path.display().to_string(), e))
+    }
+}
+
+/// For the tests: every example of every day with recorded answers should parse and solve
+#[cfg(test)]
+pub fn check_examples_solve(days: &[&dyn crate::solution::Day], data: &DataDir) {
+    let answers = crate::answers::Answers::load(data).unwrap();
+    for day in days {
+        // a day that is only scaffolded can't solve anything yet
+        if !answers.keys().any(|(n, _, _)| n == day.number()) {
+            continue;
+        }
+        for variant in data.examples(day.number()).unwrap() {
+            let raw = data.read_to_string(variant.day, variant.kind).unwrap();
+            if raw.trim().is_empty() {
+                continue;
+            }
+            for part in [crate::solution::Part::One, crate::solution::Part::Two] {
+                if let Err(e) = day.run(raw.as_str(), part) {
+                    panic!("{} part {}: {}", variant.name(), part.number(), e);
+                }
+            }
+        }
+    }
+}
+
+/// For the tests: the puzzle inputs as well as the examples should have no problems
+#[cfg(test)]
+pub fn check_inputs_validate(days: &[&dyn crate::solution::Day], data: &DataDir) {
+    for day in days {
+        for variant in data.variants(day.number()).unwrap() {
+            let raw = data.read_to_string(variant.day, variant.kind).unwrap();
+            // still waiting for the input to be pasted in
+            if raw.trim().is_empty() {
+                continue;
+            }
+            let problems: Vec<String> = day
+                .validate(raw.as_str())
+                .iter()
+                .map(|e| e.to_string())
+                .collect();
+            assert!(problems.is_empty(), "{}: {:?}", variant.name(), problems);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_names() {
+        assert_eq!("day4.txt", file_name(4, Kind::Puzzle));
+        assert_eq!("day4_test.txt", file_name(4, Kind::Example(None)));
+        assert_eq!("day12_test_3.txt", file_name(12, Kind::Example(Some(3))));
+
+        assert_eq!(Some(Kind::Puzzle), parse_name(1, "day1.txt"));
+        assert_eq!(Some(Kind::Example(None)), parse_name(1, "day1_test.txt"));
+        assert_eq!(
+            Some(Kind::Example(Some(2))),
+            parse_name(1, "day1_test_2.txt")
+        );
+        // day1 is a prefix of day12
+        assert_eq!(None, parse_name(1, "day12.txt"));
+        assert_eq!(None, parse_name(1, "day1_test_x.txt"));
+        assert_eq!(None, parse_name(1, "day1.csv"));
+    }
+
+    #[test]
+    fn test_variants() {
+        let data = DataDir::new(Path::new(env!("CARGO_MANIFEST_DIR")).join("data"));
+
+        let names: Vec<String> = data.variants(1).unwrap().iter().map(|v| v.name()).collect();
+        assert_eq!(vec!["day1.txt", "day1_test.txt"], names);
+
+        assert_eq!(3, data.examples(12).unwrap().len());
+        assert!(data.variants(25).unwrap().is_empty());
+
+        assert_matches!(
+            DataDir::new("/not/exist").variants(1),
+            Err(Error::Io { .. })
+        );
+    }
+
+    #[test]
+    fn test_examples_solve() {
+        check_examples_solve(crate::DAYS, &DataDir::locate());
+    }
+
+    #[test]
+    fn test_inputs_validate() {
+        check_inputs_validate(crate::DAYS, &DataDir::locate());
+    }
+}
@@ -0,0 +1,394 @@
+use crate::error::{Error, Result};
+use std::ops::{Index, IndexMut};
+
+/// A rectangular grid stored row by row in one Vec.
+///
+/// Cells are addressed as `(x, y)`, with x counting columns from the left
+/// and y counting rows from the top.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+/// (dx, dy) to the four orthogonal neighbours
+const ORTHOGONAL: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+/// (dx, dy) to all eight neighbours, including the diagonals
+const ALL_AROUND: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+impl<T> Grid<T> {
+    pub fn from_fn(width: usize, height: usize, f: impl Fn(usize, usize) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| f(x, y))
+            .collect();
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Build a grid from its rows, which must all be the same length
+    /// ```
+    /// use aoc2021::util::Grid;
+    ///
+    /// let grid = Grid::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]).unwrap();
+    /// assert_eq!((3, 2), (grid.width(), grid.height()));
+    /// assert_eq!(6, grid[(2, 1)]);
+    ///
+    /// assert!(Grid::from_rows(vec![vec![1, 2], vec![3]]).is_err());
+    /// ```
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self> {
+        let width = rows.first().map_or(0, |row| row.len());
+        let height = rows.len();
+        let mut cells = Vec::with_capacity(width * height);
+        for (y, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                return Err(Error::parse(
+                    y + 1,
+                    0,
+                    format!("expected {} columns, found {}", width, row.len()),
+                ));
+            }
+            cells.extend(row);
+        }
+        Ok(Grid {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// Parse one row per non-blank line, converting each character with
+    /// `f`, which returns None for characters that don't belong.
+    pub fn parse_chars(raw: &str, f: impl Fn(char) -> Option<T>) -> Result<Self> {
+        let mut rows = Vec::new();
+        let mut width = None;
+        for (i, line) in raw.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let row = line
+                .chars()
+                .enumerate()
+                .map(|(j, c)| {
+                    f(c).ok_or_else(|| Error::parse(i + 1, j + 1, format!("unexpected: {}", c)))
+                })
+                .collect::<Result<Vec<T>>>()?;
+            match width {
+                Some(width) if width != row.len() => {
+                    return Err(Error::parse(
+                        i + 1,
+                        0,
+                        format!("expected {} columns, found {}", width, row.len()),
+                    ))
+                }
+                _ => width = Some(row.len()),
+            }
+            rows.push(row);
+        }
+        Grid::from_rows(rows)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (x, y): (usize, usize)) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, position: (usize, usize)) -> Option<&T> {
+        if self.contains(position) {
+            Some(&self.cells[position.1 * self.width + position.0])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, position: (usize, usize)) -> Option<&mut T> {
+        if self.contains(position) {
+            Some(&mut self.cells[position.1 * self.width + position.0])
+        } else {
+            None
+        }
+    }
+
+    fn offsets(
+        &self,
+        (x, y): (usize, usize),
+        offsets: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        offsets.iter().filter_map(move |(dx, dy)| {
+            let position = (x.checked_add_signed(*dx)?, y.checked_add_signed(*dy)?);
+            if self.contains(position) {
+                Some(position)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// The positions above, left, right and below, where they are in the grid
+    /// ```
+    /// use aoc2021::util::Grid;
+    ///
+    /// let grid = Grid::from_fn(3, 3, |x, y| x + y);
+    /// assert_eq!(vec![(1, 0), (0, 1)], grid.neighbors4((0, 0)).collect::<Vec<_>>());
+    /// assert_eq!(4, grid.neighbors4((1, 1)).count());
+    /// ```
+    pub fn neighbors4(
+        &self,
+        position: (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offsets(position, &ORTHOGONAL)
+    }
+
+    /// Like `neighbors4`, including the diagonals
+    pub fn neighbors8(
+        &self,
+        position: (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offsets(position, &ALL_AROUND)
+    }
+
+    /// Every position, row by row
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// Every cell with its position, row by row
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks(0) panics, and an empty grid has no rows anyway
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {} is outside the grid", x);
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// One character per cell and a newline after each row
+    pub fn render(&self, f: impl Fn(&T) -> char) -> String {
+        let mut out = String::with_capacity((self.width + 1) * self.height);
+        for row in self.rows() {
+            out.extend(row.iter().map(&f));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Swap rows and columns
+    /// ```
+    /// use aoc2021::util::Grid;
+    ///
+    /// let grid = Grid::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]).unwrap();
+    /// let expected = Grid::from_rows(vec![vec![1, 4], vec![2, 5], vec![3, 6]]).unwrap();
+    /// assert_eq!(expected, grid.transpose());
+    /// ```
+    pub fn transpose(&self) -> Self {
+        Grid::from_fn(self.height, self.width, |x, y| self[(y, x)].clone())
+    }
+
+    /// Mirror left to right
+    pub fn flip_horizontal(&self) -> Self {
+        Grid::from_fn(self.width, self.height, |x, y| {
+            self[(self.width - 1 - x, y)].clone()
+        })
+    }
+
+    /// Mirror top to bottom
+    pub fn flip_vertical(&self) -> Self {
+        Grid::from_fn(self.width, self.height, |x, y| {
+            self[(x, self.height - 1 - y)].clone()
+        })
+    }
+}
+
+impl Grid<u8> {
+    /// Parse lines of single digits such as "2199943210"
+    pub fn parse_digits(raw: &str) -> Result<Self> {
+        Grid::parse_chars(raw, |c| c.to_digit(10).map(|d| d as u8))
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: (usize, usize)) -> &T {
+        self.get(position).unwrap_or_else(|| {
+            panic!(
+                "{:?} is outside the {}x{} grid",
+                position, self.width, self.height
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, position: (usize, usize)) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(position)
+            .unwrap_or_else(|| panic!("{:?} is outside the {}x{} grid", position, width, height))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn sample() -> Grid<u8> {
+        Grid::parse_digits("123\n456\n").unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = sample();
+        assert_eq!(3, grid.width());
+        assert_eq!(2, grid.height());
+        assert_eq!(Some(&4), grid.get((0, 1)));
+        assert_eq!(None, grid.get((3, 0)));
+        assert_eq!(None, grid.get((0, 2)));
+
+        assert_matches!(
+            Grid::parse_digits("123\n4x6"),
+            Err(Error::Parse {
+                line: 2,
+                column: 2,
+                ..
+            })
+        );
+        assert_matches!(
+            Grid::parse_digits("123\n\n45"),
+            Err(Error::Parse { line: 3, .. })
+        );
+
+        let chars = Grid::parse_chars("#.\n.#", |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!("#.\n.#\n", chars.render(|v| if *v { '#' } else { '.' }));
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = Grid::new(3, 3, 0);
+
+        assert_eq!(2, grid.neighbors4((0, 0)).count());
+        assert_eq!(3, grid.neighbors4((1, 0)).count());
+        assert_eq!(3, grid.neighbors8((2, 2)).count());
+        assert_eq!(5, grid.neighbors8((0, 1)).count());
+
+        let mut around: Vec<(usize, usize)> = grid.neighbors8((1, 1)).collect();
+        around.sort();
+        assert_eq!(
+            vec![
+                (0, 0),
+                (0, 1),
+                (0, 2),
+                (1, 0),
+                (1, 2),
+                (2, 0),
+                (2, 1),
+                (2, 2)
+            ],
+            around
+        );
+
+        // a single cell has no neighbours
+        assert_eq!(0, Grid::new(1, 1, 0).neighbors8((0, 0)).count());
+    }
+
+    #[test]
+    fn test_views() {
+        let grid = sample();
+        assert_eq!(&[4, 5, 6], grid.row(1));
+        assert_eq!(vec![&2, &5], grid.column(1).collect::<Vec<_>>());
+        assert_eq!(2, grid.rows().count());
+        assert_eq!(
+            vec![((0, 0), &1), ((1, 0), &2), ((2, 0), &3), ((0, 1), &4)],
+            grid.iter().take(4).collect::<Vec<_>>()
+        );
+        assert_eq!(0, Grid::<u8>::parse_digits("").unwrap().rows().count());
+    }
+
+    #[test]
+    fn test_transform() {
+        let grid = sample();
+        assert_eq!(
+            Grid::parse_digits("321\n654").unwrap(),
+            grid.flip_horizontal()
+        );
+        assert_eq!(
+            Grid::parse_digits("456\n123").unwrap(),
+            grid.flip_vertical()
+        );
+        assert_eq!(grid, grid.transpose().transpose());
+        assert_eq!(Grid::parse_digits("14\n25\n36").unwrap(), grid.transpose());
+
+        let mut grid = grid.map(|v| *v as u32 * 10);
+        grid[(2, 1)] += 1;
+        assert_eq!(61, grid[(2, 1)]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_index_outside() {
+        let _ = sample()[(0, 2)];
+    }
+}
@@ -0,0 +1,10 @@
+use std::fs;
+use std::path::PathBuf;
+
+/// For the tests: an empty directory of their own, named for the process and `name`
+pub fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("aoc2021-{}-{}", std::process::id(), name));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
@@ -0,0 +1,338 @@
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+use std::ops::{Add, Sub};
+
+/// A summary of the values in a window, kept up to date as each value
+/// enters at the back and later leaves from the front, so that sliding
+/// the window along never has to look at every value again.
+pub trait Aggregate<T> {
+    type Output;
+
+    /// `value` has just entered the window
+    fn push(&mut self, value: &T);
+
+    /// `value`, the oldest in the window, has just left it
+    fn pop(&mut self, value: &T);
+
+    fn value(&self) -> Self::Output;
+}
+
+/// Running total of the window
+#[derive(Clone, Debug, Default)]
+pub struct Sum<T> {
+    total: T,
+}
+
+impl<T> Aggregate<T> for Sum<T>
+where
+    T: Copy + Add<Output = T> + Sub<Output = T>,
+{
+    type Output = T;
+
+    fn push(&mut self, value: &T) {
+        self.total = self.total + *value;
+    }
+
+    fn pop(&mut self, value: &T) {
+        self.total = self.total - *value;
+    }
+
+    fn value(&self) -> T {
+        self.total
+    }
+}
+
+/// Keeps only the values that could still become the minimum (or maximum)
+/// once older values leave, so each value is added and removed at most once.
+#[derive(Clone, Debug)]
+struct Monotonic<T> {
+    candidates: VecDeque<T>,
+    /// true when `a` makes `b` irrelevant as long as `a` is in the window
+    beats: fn(&T, &T) -> bool,
+}
+
+impl<T: Clone> Monotonic<T> {
+    fn push(&mut self, value: &T) {
+        while let Some(last) = self.candidates.back() {
+            if (self.beats)(value, last) {
+                self.candidates.pop_back();
+            } else {
+                break;
+            }
+        }
+        self.candidates.push_back(value.clone());
+    }
+
+    fn pop(&mut self, value: &T)
+    where
+        T: PartialEq,
+    {
+        if self.candidates.front() == Some(value) {
+            self.candidates.pop_front();
+        }
+    }
+}
+
+/// Smallest value in the window
+#[derive(Clone, Debug)]
+pub struct Min<T>(Monotonic<T>);
+
+impl<T: PartialOrd> Default for Min<T> {
+    fn default() -> Self {
+        Min(Monotonic {
+            candidates: VecDeque::new(),
+            beats: |a, b| a < b,
+        })
+    }
+}
+
+impl<T: Clone + PartialOrd> Aggregate<T> for Min<T> {
+    type Output = T;
+
+    fn push(&mut self, value: &T) {
+        self.0.push(value)
+    }
+
+    fn pop(&mut self, value: &T) {
+        self.0.pop(value)
+    }
+
+    fn value(&self) -> T {
+        self.0.candidates.front().cloned().expect("window is empty")
+    }
+}
+
+/// Largest value in the window
+#[derive(Clone, Debug)]
+pub struct Max<T>(Monotonic<T>);
+
+impl<T: PartialOrd> Default for Max<T> {
+    fn default() -> Self {
+        Max(Monotonic {
+            candidates: VecDeque::new(),
+            beats: |a, b| a > b,
+        })
+    }
+}
+
+impl<T: Clone + PartialOrd> Aggregate<T> for Max<T> {
+    type Output = T;
+
+    fn push(&mut self, value: &T) {
+        self.0.push(value)
+    }
+
+    fn pop(&mut self, value: &T) {
+        self.0.pop(value)
+    }
+
+    fn value(&self) -> T {
+        self.0.candidates.front().cloned().expect("window is empty")
+    }
+}
+
+/// Any other running value, given how to add a value to it and how to take
+/// one back out again.
+/// ```
+/// use aoc2021::util::{Fold, SlidingWindows};
+///
+/// // how many values in each window of 3 are even
+/// let even = |n: &i32| (n % 2 == 0) as usize;
+/// let counts: Vec<usize> = [1, 2, 4, 5, 7, 8]
+///     .into_iter()
+///     .sliding(3, Fold::new(0, move |c, n| c + even(n), move |c, n| c - even(n)))
+///     .collect();
+/// assert_eq!(vec![2, 2, 1, 1], counts);
+/// ```
+pub struct Fold<T, A, F, G> {
+    acc: A,
+    add: F,
+    remove: G,
+    _values: PhantomData<fn(&T)>,
+}
+
+impl<T, A, F, G> Fold<T, A, F, G>
+where
+    F: Fn(A, &T) -> A,
+    G: Fn(A, &T) -> A,
+{
+    pub fn new(init: A, add: F, remove: G) -> Self {
+        Fold {
+            acc: init,
+            add,
+            remove,
+            _values: PhantomData,
+        }
+    }
+}
+
+impl<T, A, F, G> Aggregate<T> for Fold<T, A, F, G>
+where
+    A: Clone,
+    F: Fn(A, &T) -> A,
+    G: Fn(A, &T) -> A,
+{
+    type Output = A;
+
+    fn push(&mut self, value: &T) {
+        self.acc = (self.add)(self.acc.clone(), value);
+    }
+
+    fn pop(&mut self, value: &T) {
+        self.acc = (self.remove)(self.acc.clone(), value);
+    }
+
+    fn value(&self) -> A {
+        self.acc.clone()
+    }
+}
+
+/// Iterator over an aggregate of every full window of `size` values
+pub struct Windows<I: Iterator, A> {
+    iter: I,
+    size: usize,
+    window: VecDeque<I::Item>,
+    aggregate: A,
+}
+
+impl<I, A> Iterator for Windows<I, A>
+where
+    I: Iterator,
+    A: Aggregate<I::Item>,
+{
+    type Item = A::Output;
+
+    fn next(&mut self) -> Option<A::Output> {
+        // the oldest value only leaves once the next one is wanted
+        if self.window.len() == self.size {
+            if let Some(oldest) = self.window.pop_front() {
+                self.aggregate.pop(&oldest);
+            }
+        }
+        while self.window.len() < self.size {
+            let value = self.iter.next()?;
+            self.aggregate.push(&value);
+            self.window.push_back(value);
+        }
+        Some(self.aggregate.value())
+    }
+}
+
+/// Sliding window adapters for any iterator
+pub trait SlidingWindows: Iterator + Sized {
+    /// Aggregate each window of `size` consecutive values, which must be at
+    /// least 1. There are no windows if there are fewer than `size` values.
+    fn sliding<A: Aggregate<Self::Item>>(self, size: usize, aggregate: A) -> Windows<Self, A> {
+        assert!(size > 0, "window size must be at least 1");
+        Windows {
+            iter: self,
+            size,
+            window: VecDeque::with_capacity(size),
+            aggregate,
+        }
+    }
+
+    /// ```
+    /// use aoc2021::util::SlidingWindows;
+    ///
+    /// let sums: Vec<i32> = [1, 2, 3, 4].into_iter().sliding_sum(3).collect();
+    /// assert_eq!(vec![6, 9], sums);
+    /// ```
+    fn sliding_sum(self, size: usize) -> Windows<Self, Sum<Self::Item>>
+    where
+        Self::Item: Copy + Default + Add<Output = Self::Item> + Sub<Output = Self::Item>,
+    {
+        self.sliding(size, Sum::default())
+    }
+
+    fn sliding_min(self, size: usize) -> Windows<Self, Min<Self::Item>>
+    where
+        Self::Item: Clone + PartialOrd,
+    {
+        self.sliding(size, Min::default())
+    }
+
+    fn sliding_max(self, size: usize) -> Windows<Self, Max<Self::Item>>
+    where
+        Self::Item: Clone + PartialOrd,
+    {
+        self.sliding(size, Max::default())
+    }
+}
+
+impl<I: Iterator> SlidingWindows for I {}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_add_triples() {
+        let records = [1, 2, 3, 4];
+        let mut triple = records.iter().copied().sliding_sum(3);
+
+        assert_matches!(triple.next(), Some(value) => {
+            assert_eq!(6, value)
+        });
+
+        assert_matches!(triple.next(), Some(value) => {
+            assert_eq!(9, value)
+        });
+
+        assert_matches!(triple.next(), None);
+    }
+
+    #[test]
+    fn test_sizes() {
+        let values = [5u64, 1, 4, 2, 8];
+        let sums = |n| values.iter().copied().sliding_sum(n).collect::<Vec<u64>>();
+        assert_eq!(values.to_vec(), sums(1));
+        assert_eq!(vec![6, 5, 6, 10], sums(2));
+        assert_eq!(vec![20], sums(5));
+        assert!(sums(6).is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_empty_window() {
+        let _ = [1].iter().copied().sliding_sum(0);
+    }
+
+    #[test]
+    fn test_min_max() {
+        let values = [3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5];
+        for size in 1..=values.len() {
+            let mins: Vec<i32> = values.iter().copied().sliding_min(size).collect();
+            let maxes: Vec<i32> = values.iter().copied().sliding_max(size).collect();
+
+            let expected_mins: Vec<i32> = values
+                .windows(size)
+                .map(|w| *w.iter().min().unwrap())
+                .collect();
+            let expected_maxes: Vec<i32> = values
+                .windows(size)
+                .map(|w| *w.iter().max().unwrap())
+                .collect();
+            assert_eq!(expected_mins, mins, "size {}", size);
+            assert_eq!(expected_maxes, maxes, "size {}", size);
+        }
+
+        // not just numbers
+        let words: Vec<&str> = ["pear", "apple", "fig", "kiwi"]
+            .into_iter()
+            .sliding_min(2)
+            .collect();
+        assert_eq!(vec!["apple", "apple", "fig"], words);
+    }
+
+    #[test]
+    fn test_fold() {
+        // product of non-zero values, undone by division
+        let products: Vec<i64> = [2i64, 3, 4, 5]
+            .into_iter()
+            .sliding(2, Fold::new(1, |p, v| p * v, |p, v| p / v))
+            .collect();
+        assert_eq!(vec![6, 12, 20], products);
+    }
+}
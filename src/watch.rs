@@ -0,0 +1,303 @@
+//! Re-solving a day whenever one of its data files changes, by polling them
+
+use crate::error::{Error, Result};
+use crate::run_all::{self, Outcome};
+use crate::solution::Day;
+use crate::util::DataDir;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::io::prelude::*;
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+pub const USAGE: &str = "usage: watch [--interval MS] [--data DIR] DAY";
+
+/// When each of a day's files was last modified, and how long it was
+pub type Snapshot = BTreeMap<PathBuf, (SystemTime, u64)>;
+
+pub fn snapshot(data: &DataDir, day: u8) -> Result<Snapshot> {
+    let mut files = Snapshot::new();
+    for variant in data.variants(day)? {
+        let context = variant.path.display().to_string();
+        let meta = match fs::metadata(&variant.path) {
+            Ok(meta) => meta,
+            // an editor saving it by replacing it, say
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(Error::io(context.as_str(), e)),
+        };
+        let modified = meta
+            .modified()
+            .map_err(|e| Error::io(context.as_str(), e))?;
+        files.insert(variant.path, (modified, meta.len()));
+    }
+    Ok(files)
+}
+
+/// the files that were added, removed or modified between two snapshots
+pub fn changed(before: &Snapshot, after: &Snapshot) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = before
+        .keys()
+        .chain(after.keys())
+        .filter(|path| before.get(*path) != after.get(*path))
+        .cloned()
+        .collect();
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+fn answer_text(outcome: &Outcome) -> String {
+    match &outcome.answer {
+        Ok(answer) => answer.clone(),
+        Err(failure) => failure.to_string(),
+    }
+}
+
+/// Write every answer, marked `*` if changed, `+` if new or `-` if gone
+pub fn write_diff(before: &[Outcome], after: &[Outcome], out: &mut impl Write) -> io::Result<()> {
+    let key = |o: &Outcome| (o.input.clone(), o.part.number());
+    let previous: BTreeMap<_, String> = before.iter().map(|o| (key(o), answer_text(o))).collect();
+
+    for o in after {
+        let answer = answer_text(o);
+        let label = format!("{} part {}", o.input, o.part.number());
+        match previous.get(&key(o)) {
+            None => writeln!(out, "+ {}: {}", label, answer)?,
+            Some(was) if *was != answer => writeln!(out, "* {}: {} (was {})", label, answer, was)?,
+            Some(_) => writeln!(out, "  {}: {}", label, answer)?,
+        }
+    }
+
+    let current: Vec<_> = after.iter().map(key).collect();
+    for o in before.iter().filter(|o| !current.contains(&key(o))) {
+        writeln!(
+            out,
+            "- {} part {}: {}",
+            o.input,
+            o.part.number(),
+            answer_text(o)
+        )?;
+    }
+    Ok(())
+}
+
+/// Solves a day again each time its files change
+pub struct Watcher {
+    day: &'static dyn Day,
+    data: DataDir,
+    snapshot: Option<Snapshot>,
+    outcomes: Vec<Outcome>,
+}
+
+impl Watcher {
+    pub fn new(day: &'static dyn Day, data: DataDir) -> Self {
+        Watcher {
+            day,
+            data,
+            snapshot: None,
+            outcomes: Vec::new(),
+        }
+    }
+
+    /// Solve every input again if any file changed, returning whether it did
+    pub fn poll(&mut self, out: &mut impl Write) -> Result<bool> {
+        let output = |e| Error::io("output", e);
+        let snapshot = snapshot(&self.data, self.day.number())?;
+        match &self.snapshot {
+            Some(before) => {
+                let paths = changed(before, &snapshot);
+                if paths.is_empty() {
+                    return Ok(false);
+                }
+                let names: Vec<String> = paths
+                    .iter()
+                    .filter_map(|p| p.file_name())
+                    .map(|n| n.to_string_lossy().to_string())
+                    .collect();
+                writeln!(out, "changed: {}", names.join(", ")).map_err(output)?;
+            }
+            None => writeln!(out, "watching day {}", self.day.number()).map_err(output)?,
+        }
+        self.snapshot = Some(snapshot);
+
+        let jobs = run_all::collect_jobs(&self.data, &[self.day])?;
+        let outcomes = run_all::run_jobs(&jobs, 1);
+        write_diff(&self.outcomes, &outcomes, out).map_err(output)?;
+        self.outcomes = outcomes;
+        Ok(true)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Options {
+    pub day: u8,
+    /// how long to wait between looking at the files
+    pub interval: Duration,
+    pub data: DataDir,
+}
+
+/// Parse the command line arguments (without the program name)
+pub fn parse_args(args: impl Iterator<Item = String>) -> std::result::Result<Options, String> {
+    let mut day = None;
+    let mut interval = Duration::from_millis(500);
+    let mut data = None;
+
+    let mut args = args;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-n" | "--interval" => {
+                let value = args.next().ok_or("--interval needs a value")?;
+                interval = match value.parse::<u64>() {
+                    Ok(ms) if ms > 0 => Duration::from_millis(ms),
+                    _ => return Err(format!("not an interval: {}", value)),
+                };
+            }
+            "-d" | "--data" => {
+                data = Some(DataDir::new(args.next().ok_or("--data needs a value")?));
+            }
+            value if day.is_none() => {
+                day = Some(
+                    value
+                        .parse::<u8>()
+                        .map_err(|_| format!("not a day: {}", value))?,
+                );
+            }
+            value => return Err(format!("unexpected argument: {}", value)),
+        }
+    }
+
+    let day = day.ok_or("no day given")?;
+    let data = data.unwrap_or_else(DataDir::locate);
+    Ok(Options {
+        day,
+        interval,
+        data,
+    })
+}
+
+/// Watch the day until something goes wrong
+pub fn run(options: &Options, out: &mut impl Write) -> Result<()> {
+    let day = crate::day(options.day)
+        .ok_or_else(|| Error::invalid(format!("day {} is not implemented", options.day)))?;
+    let mut watcher = Watcher::new(day, options.data.clone());
+    loop {
+        if watcher.poll(out)? {
+            out.flush().map_err(|e| Error::io("output", e))?;
+        }
+        thread::sleep(options.interval);
+    }
+}
+
+/// Entry point for the watch binary, returns the process exit code
+pub fn main() -> i32 {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(msg) => {
+            eprintln!("{}\n{}", msg, USAGE);
+            return 2;
+        }
+    };
+
+    match run(&options, &mut io::stdout()) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("error: day {}: {}", options.day, e);
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use std::fs::File;
+
+    fn args(values: &[&str]) -> impl Iterator<Item = String> {
+        values
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<String>>()
+            .into_iter()
+    }
+
+    /// write a file modified some seconds from now, so quick writes still look modified
+    fn write(path: PathBuf, contents: &str, seconds: u64) {
+        fs::write(&path, contents).unwrap();
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(seconds))
+            .unwrap();
+    }
+
+    fn poll(watcher: &mut Watcher) -> Option<String> {
+        let mut out = Vec::new();
+        let solved = watcher.poll(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(solved, !out.is_empty());
+        Some(out).filter(|_| solved)
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_matches!(parse_args(args(&["--interval", "100", "13"])), Ok(options) => {
+            assert_eq!(13, options.day);
+            assert_eq!(Duration::from_millis(100), options.interval);
+        });
+        assert!(parse_args(args(&[])).is_err());
+        assert!(parse_args(args(&["-n", "0", "13"])).is_err());
+        assert!(parse_args(args(&["13", "14"])).is_err());
+    }
+
+    #[test]
+    fn test_watch() {
+        let data = DataDir::new(crate::util::scratch_dir("watch"));
+        write(data.puzzle(1), "1\n2\n3\n", 1);
+        let mut watcher = Watcher::new(crate::day(1).unwrap(), data.clone());
+
+        assert_eq!(
+            Some("watching day 1\n+ day1.txt part 1: 2\n+ day1.txt part 2: 0\n".to_string()),
+            poll(&mut watcher)
+        );
+        assert_eq!(None, poll(&mut watcher));
+
+        write(data.puzzle(1), "1\n2\n3\n4\n", 2);
+        write(data.example(1, None), "2\n1\n", 2);
+        assert_eq!(
+            Some(
+                concat!(
+                    "changed: day1.txt, day1_test.txt\n",
+                    "* day1.txt part 1: 3 (was 2)\n",
+                    "* day1.txt part 2: 1 (was 0)\n",
+                    "+ day1_test.txt part 1: 0\n",
+                    "+ day1_test.txt part 2: 0\n",
+                )
+                .to_string()
+            ),
+            poll(&mut watcher)
+        );
+
+        // a broken input is reported in place of its answers
+        fs::remove_file(data.example(1, None)).unwrap();
+        write(data.puzzle(1), "1\nx\n", 3);
+        assert_eq!(
+            Some(
+                concat!(
+                    "changed: day1.txt, day1_test.txt\n",
+                    "* day1.txt part 1: error: line 2, column 1: not a number: x (was 3)\n",
+                    "* day1.txt part 2: error: line 2, column 1: not a number: x (was 1)\n",
+                    "- day1_test.txt part 1: 0\n",
+                    "- day1_test.txt part 2: 0\n",
+                )
+                .to_string()
+            ),
+            poll(&mut watcher)
+        );
+
+        fs::remove_dir_all(data.root()).unwrap();
+    }
+}